@@ -238,11 +238,11 @@ def main(args):
             # Make statistics and output the total metadata in the original order
             metadata_ordered_item = ["proper_trace_count", "path_unsat_trace_count", "branch_explored_trace_count",
                 "duplicate_trace_count", "no_target_trace_count", "exceeding_length_trace_count",
-                "timeout_trace_count", "unreachable_trace_count", "explored_trace_count"]
+                "timeout_trace_count", "unreachable_trace_count", "unwound_trace_count", "explored_trace_count"]
             total_metadata = combine_metadata(args.outdir, bc_files_to_run, tmp_folder_name)
             ordered_metadata = dict()
             for item in metadata_ordered_item:
-                ordered_metadata[item] = total_metadata[item]
+                ordered_metadata[item] = total_metadata.get(item, 0)
             logger.info(utils.color_str("Total Metadata: " + str(ordered_metadata), "cyan"))
 
             if not args.trace_only:
@@ -1,13 +1,14 @@
 #[allow(deprecated)]
 use llvm_sys::bit_reader::LLVMParseBitcodeInContext;
 use llvm_sys::core::*;
+use llvm_sys::ir_reader::LLVMParseIRInContext;
 use llvm_sys::prelude::LLVMContextRef;
 use std::mem::MaybeUninit;
 use std::path::Path;
 
 use super::utils::mem_buffer::MemoryBuffer;
 use super::utils::support::LLVMString;
-use super::Module;
+use super::{Diagnostic, Module};
 
 /// LLVM Context
 ///
@@ -33,7 +34,7 @@ impl Context {
     Self::new(context)
   }
 
-  /// Load a module
+  /// Load a module from a bitcode file or a textual IR (`.ll`) file
   ///
   /// ```
   /// # use llir::Context;
@@ -45,6 +46,43 @@ impl Context {
     P: AsRef<Path>,
   {
     let buffer = MemoryBuffer::create_from_file(path.as_ref())?;
+    if buffer.is_bitcode() {
+      self.parse_bitcode(buffer)
+    } else {
+      let name = path.as_ref().to_string_lossy();
+      self.parse_ir_buffer(buffer, &name).map_err(String::from)
+    }
+  }
+
+  /// Load a module from the bytes of bitcode or textual IR, e.g. an object extracted from an archive
+  ///
+  /// ```
+  /// # use llir::Context;
+  /// # let ctx = Context::create();
+  /// let bytes = std::fs::read("tests/c_files/basic/example_1.bc")?;
+  /// let module = ctx.load_module_from_bytes(&bytes)?;
+  /// ```
+  pub fn load_module_from_bytes<'ctx>(&'ctx self, bytes: &[u8]) -> Result<Module<'ctx>, String> {
+    let buffer = MemoryBuffer::create_from_bytes(bytes, "<bytes>");
+    if buffer.is_bitcode() {
+      self.parse_bitcode(buffer)
+    } else {
+      self.parse_ir_buffer(buffer, "<bytes>").map_err(String::from)
+    }
+  }
+
+  /// Parse a module from textual IR
+  ///
+  /// ```
+  /// # use llir::Context;
+  /// # let ctx = Context::create();
+  /// let module = ctx.parse_ir("define i32 @f() {\n  ret i32 0\n}\n")?;
+  /// ```
+  pub fn parse_ir<'ctx>(&'ctx self, text: &str) -> Result<Module<'ctx>, Diagnostic> {
+    self.parse_ir_buffer(MemoryBuffer::create_from_bytes(text.as_bytes(), "<string>"), "<string>")
+  }
+
+  fn parse_bitcode<'ctx>(&'ctx self, buffer: MemoryBuffer) -> Result<Module<'ctx>, String> {
     let mut module = MaybeUninit::uninit();
     let mut err_string = MaybeUninit::uninit();
     #[allow(deprecated)]
@@ -65,6 +103,29 @@ impl Context {
 
     Ok(Module::new(module))
   }
+
+  fn parse_ir_buffer<'ctx>(&'ctx self, buffer: MemoryBuffer, name: &str) -> Result<Module<'ctx>, Diagnostic> {
+    let mut module = MaybeUninit::uninit();
+    let mut err_string = MaybeUninit::uninit();
+    let success = unsafe {
+      LLVMParseIRInContext(
+        self.0,
+        buffer.memory_buffer,
+        module.as_mut_ptr(),
+        err_string.as_mut_ptr(),
+      )
+    };
+    // The buffer is taken and disposed by LLVM
+    std::mem::forget(buffer);
+    if success != 0 {
+      let err_string = LLVMString::new(unsafe { err_string.assume_init() });
+      return Err(Diagnostic::from_llvm_message(name, &err_string.to_string()));
+    }
+
+    let module = unsafe { module.assume_init() };
+
+    Ok(Module::new(module))
+  }
 }
 
 impl Drop for Context {
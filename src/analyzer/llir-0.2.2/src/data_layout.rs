@@ -0,0 +1,39 @@
+use llvm_sys::core::LLVMTypeIsSized;
+use llvm_sys::target::{LLVMABISizeOfType, LLVMOffsetOfElement, LLVMTargetDataRef};
+use std::marker::PhantomData;
+
+use crate::types::*;
+use crate::TypeRef;
+
+/// [Data layout](https://llvm.org/docs/LangRef.html#data-layout) of a module
+///
+/// Gives the sizes of types and the offsets of struct elements on the target, in bytes
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DataLayout<'ctx>(LLVMTargetDataRef, PhantomData<&'ctx ()>);
+
+impl_send_sync!(DataLayout);
+
+impl<'ctx> DataLayout<'ctx> {
+  pub(crate) fn new(ptr: LLVMTargetDataRef) -> Self {
+    Self(ptr, PhantomData)
+  }
+
+  /// Get the ABI size of a type in bytes, including the tail padding; `None` if the type is
+  /// not sized, e.g. an opaque struct or a function type
+  pub fn abi_size_of<T: TypeRef>(&self, ty: &T) -> Option<u64> {
+    if unsafe { LLVMTypeIsSized(ty.type_ref()) } != 0 {
+      Some(unsafe { LLVMABISizeOfType(self.0, ty.type_ref()) })
+    } else {
+      None
+    }
+  }
+
+  /// Get the offset in bytes of the element at `index` of a struct
+  pub fn offset_of_element<T: StructTypeTrait<'ctx>>(&self, ty: &T, index: usize) -> Option<u64> {
+    if index < ty.num_element_types() && unsafe { LLVMTypeIsSized(ty.type_ref()) } != 0 {
+      Some(unsafe { LLVMOffsetOfElement(self.0, ty.type_ref(), index as u32) })
+    } else {
+      None
+    }
+  }
+}
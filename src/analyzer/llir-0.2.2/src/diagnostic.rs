@@ -0,0 +1,68 @@
+use std::fmt::{self, Display, Formatter};
+
+/// An error from parsing textual IR
+///
+/// ```
+/// # use llir::Context;
+/// # let ctx = Context::create();
+/// let err = ctx.parse_ir("define void @f() {").unwrap_err();
+/// println!("{}:{}: {}", err.line.unwrap(), err.column.unwrap(), err.message);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+  /// The name of the buffer, e.g. the path of the `.ll` file
+  pub name: String,
+  /// 1-based line number, if LLVM reports the location
+  pub line: Option<usize>,
+  /// 1-based column number, if LLVM reports the location
+  pub column: Option<usize>,
+  pub message: String,
+  /// The source line where the error is
+  pub source_line: Option<String>,
+}
+
+impl Diagnostic {
+  /// Parse the message printed by LLVM, i.e. `name:line:col: error: message` followed by the
+  /// source line and a caret line
+  pub(crate) fn from_llvm_message(name: &str, message: &str) -> Self {
+    let mut lines = message.lines();
+    let first = lines.next().unwrap_or("");
+    let (location, text) = match first.find(": error: ") {
+      Some(i) => (&first[..i], &first[i + ": error: ".len()..]),
+      None => ("", first),
+    };
+    let mut parts = location.rsplitn(3, ':');
+    let column = parts.next().and_then(|c| c.parse::<usize>().ok());
+    let line = parts.next().and_then(|l| l.parse::<usize>().ok());
+    let (line, column) = match (line, column) {
+      (Some(line), Some(column)) => (Some(line), Some(column)),
+      _ => (None, None),
+    };
+    Self {
+      name: name.to_string(),
+      line,
+      column,
+      message: text.trim().to_string(),
+      source_line: if line.is_some() {
+        lines.next().map(String::from)
+      } else {
+        None
+      },
+    }
+  }
+}
+
+impl Display for Diagnostic {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match (self.line, self.column) {
+      (Some(line), Some(column)) => write!(f, "{}:{}:{}: {}", self.name, line, column, self.message),
+      _ => write!(f, "{}: {}", self.name, self.message),
+    }
+  }
+}
+
+impl From<Diagnostic> for String {
+  fn from(diagnostic: Diagnostic) -> Self {
+    diagnostic.to_string()
+  }
+}
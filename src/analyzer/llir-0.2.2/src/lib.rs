@@ -15,7 +15,7 @@
 //! // Specify path to the byte code
 //! let path = Path::new("path/to/your/llvm/bytecode.bc");
 //!
-//! // Load the module with that path
+//! // Load the module with that path, either bitcode or textual IR
 //! let module = context.load_module(path)?;
 //!
 //! // Iterate through functions, blocks, and instructions...
@@ -35,10 +35,14 @@
 mod utils;
 
 mod context;
+mod data_layout;
+mod diagnostic;
 mod module;
 pub mod types;
 pub mod values;
 
 pub use context::*;
+pub use data_layout::*;
+pub use diagnostic::*;
 pub use module::*;
 pub use utils::traits::*;
@@ -1,7 +1,11 @@
 use llvm_sys::core::*;
+use llvm_sys::linker::LLVMLinkModules2;
 use llvm_sys::prelude::{LLVMModuleRef, LLVMValueRef};
+use llvm_sys::target::LLVMGetModuleDataLayout;
+use llvm_sys::{LLVMLinkage, LLVMVisibility};
 use std::marker::PhantomData;
 
+use crate::utils::string_of_value;
 use crate::values::*;
 use crate::*;
 
@@ -15,6 +19,58 @@ impl<'ctx> Module<'ctx> {
     Self(ptr, PhantomData)
   }
 
+  /// Link another module of the same context into this module
+  ///
+  /// The other module is consumed. Internal symbols with clashing names are renamed
+  /// by the linker, so static functions from different modules are kept apart.
+  ///
+  /// ```
+  /// # use llir::Context;
+  /// # use std::path::Path;
+  /// # let ctx = Context::create();
+  /// let module = ctx.load_module(Path::new("tests/c_files/basic/example_1.bc"))?;
+  /// let other = ctx.load_module(Path::new("tests/c_files/basic/example_2.bc"))?;
+  /// module.link(other)?;
+  /// # Ok::<(), String>(())
+  /// ```
+  pub fn link(&self, other: Module<'ctx>) -> Result<(), String> {
+    let failed = unsafe { LLVMLinkModules2(self.0, other.0) };
+    if failed != 0 {
+      Err(String::from("Cannot link modules"))
+    } else {
+      Ok(())
+    }
+  }
+
+  /// Internalize the external definitions of the other module that are also defined in this module,
+  /// returning their names
+  ///
+  /// Linking fails on such duplicate symbols. Once internalized, the definitions are renamed by the
+  /// linker like static functions, and the references across modules go to the ones in this module.
+  pub fn internalize_clashing_definitions(&self, other: &Module<'ctx>) -> Vec<String> {
+    let mut names = vec![];
+    let mut func = unsafe { LLVMGetFirstFunction(other.0) };
+    while !func.is_null() {
+      names.extend(internalize_if_clashing(func, |name| unsafe {
+        LLVMGetNamedFunction(self.0, name)
+      }));
+      func = next_function(func);
+    }
+    let mut glob = unsafe { LLVMGetFirstGlobal(other.0) };
+    while !glob.is_null() {
+      names.extend(internalize_if_clashing(glob, |name| unsafe {
+        LLVMGetNamedGlobal(self.0, name)
+      }));
+      glob = next_global(glob);
+    }
+    names
+  }
+
+  /// Get the data layout of the target of the module
+  pub fn data_layout(&self) -> DataLayout<'ctx> {
+    DataLayout::new(unsafe { LLVMGetModuleDataLayout(self.0) })
+  }
+
   /// Iterate all functions inside the module
   ///
   /// ```
@@ -102,6 +158,33 @@ impl<'ctx> Module<'ctx> {
   }
 }
 
+// A definition with external linkage, which may not be defined by more than one module
+fn is_external_definition(ptr: LLVMValueRef) -> bool {
+  unsafe { LLVMIsDeclaration(ptr) == 0 && LLVMGetLinkage(ptr) == LLVMLinkage::LLVMExternalLinkage }
+}
+
+// Internalize the definition if the one of the same name found by `lookup` is also an external
+// definition, returning the name
+fn internalize_if_clashing<F>(ptr: LLVMValueRef, lookup: F) -> Option<String>
+where
+  F: Fn(*const i8) -> LLVMValueRef,
+{
+  if !is_external_definition(ptr) {
+    return None;
+  }
+  let name = string_of_value(ptr);
+  let zero_appended = format!("{}\0", name);
+  let other = lookup(zero_appended.as_ptr() as *const i8);
+  if other.is_null() || !is_external_definition(other) {
+    return None;
+  }
+  unsafe {
+    LLVMSetLinkage(ptr, LLVMLinkage::LLVMInternalLinkage);
+    LLVMSetVisibility(ptr, LLVMVisibility::LLVMDefaultVisibility);
+  }
+  Some(name)
+}
+
 fn next_function(ptr: LLVMValueRef) -> LLVMValueRef {
   unsafe { LLVMGetNextFunction(ptr) }
 }
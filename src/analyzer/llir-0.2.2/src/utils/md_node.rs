@@ -0,0 +1,66 @@
+use llvm_sys::core::*;
+use llvm_sys::debuginfo::*;
+use llvm_sys::prelude::*;
+use std::ptr;
+
+/// Get the metadata kind of a metadata value
+pub fn md_kind(val: LLVMValueRef) -> LLVMMetadataKind {
+  unsafe { LLVMGetMetadataKind(LLVMValueAsMetadata(val)) }
+}
+
+/// Get the operands of a metadata node. Null operands are kept so that the
+/// positions match the layout of the node. A value as metadata has a single
+/// operand, the wrapped value
+pub fn md_node_operands(val: LLVMValueRef) -> Vec<LLVMValueRef> {
+  if unsafe { LLVMIsAMDNode(val) }.is_null() {
+    return vec![];
+  }
+  let num_operands = unsafe { LLVMGetMDNodeNumOperands(val) } as usize;
+  let mut operands = vec![ptr::null_mut(); num_operands];
+  unsafe { LLVMGetMDNodeOperands(val, operands.as_mut_ptr()) };
+  operands
+}
+
+/// Get the operand at a given index of a metadata node, if it is not null
+pub fn md_node_operand(val: LLVMValueRef, index: usize) -> Option<LLVMValueRef> {
+  md_node_operands(val)
+    .get(index)
+    .cloned()
+    .filter(|operand| !operand.is_null())
+}
+
+/// Get the string of a metadata string; `None` if it is not one or is empty
+pub fn md_string(val: LLVMValueRef) -> Option<String> {
+  let mut len = 0;
+  let ptr = unsafe { LLVMGetMDString(val, &mut len) };
+  if ptr.is_null() || len == 0 {
+    None
+  } else {
+    let bytes = unsafe { std::slice::from_raw_parts(ptr as *const u8, len as usize) };
+    Some(String::from_utf8_lossy(bytes).into_owned())
+  }
+}
+
+/// Turn a metadata into value, in the context of another value
+pub fn md_as_value(context_of: LLVMValueRef, md: LLVMMetadataRef) -> Option<LLVMValueRef> {
+  if md.is_null() {
+    None
+  } else {
+    Some(unsafe { LLVMMetadataAsValue(LLVMGetTypeContext(LLVMTypeOf(context_of)), md) })
+  }
+}
+
+/// Get the filename of the file of a debug info scope
+pub fn di_file_name(file: LLVMMetadataRef) -> Option<String> {
+  if file.is_null() {
+    return None;
+  }
+  let mut len = 0;
+  let ptr = unsafe { LLVMDIFileGetFilename(file, &mut len) };
+  if ptr.is_null() || len == 0 {
+    None
+  } else {
+    let bytes = unsafe { std::slice::from_raw_parts(ptr as *const u8, len as usize) };
+    Some(String::from_utf8_lossy(bytes).into_owned())
+  }
+}
@@ -1,4 +1,7 @@
-use llvm_sys::core::{LLVMCreateMemoryBufferWithContentsOfFile, LLVMDisposeMemoryBuffer};
+use llvm_sys::core::{
+  LLVMCreateMemoryBufferWithContentsOfFile, LLVMCreateMemoryBufferWithMemoryRangeCopy, LLVMDisposeMemoryBuffer,
+  LLVMGetBufferSize, LLVMGetBufferStart,
+};
 use llvm_sys::prelude::LLVMMemoryBufferRef;
 
 use super::support::{to_c_str, LLVMString};
@@ -39,6 +42,34 @@ impl MemoryBuffer {
 
     Ok(MemoryBuffer::new(memory_buffer))
   }
+
+  /// Copy the bytes into a new buffer. The name shows up in the diagnostics
+  pub fn create_from_bytes(bytes: &[u8], name: &str) -> Self {
+    let name = to_c_str(name);
+    let memory_buffer = unsafe {
+      LLVMCreateMemoryBufferWithMemoryRangeCopy(
+        bytes.as_ptr() as *const ::libc::c_char,
+        bytes.len(),
+        name.as_ptr() as *const ::libc::c_char,
+      )
+    };
+    MemoryBuffer::new(memory_buffer)
+  }
+
+  pub fn as_bytes(&self) -> &[u8] {
+    unsafe {
+      let start = LLVMGetBufferStart(self.memory_buffer) as *const u8;
+      std::slice::from_raw_parts(start, LLVMGetBufferSize(self.memory_buffer))
+    }
+  }
+
+  /// Whether the buffer holds bitcode, either raw or in the wrapper, rather than textual IR
+  pub fn is_bitcode(&self) -> bool {
+    match self.as_bytes() {
+      [b'B', b'C', 0xc0, 0xde, ..] | [0xde, 0xc0, 0x17, 0x0b, ..] => true,
+      _ => false,
+    }
+  }
 }
 
 impl Drop for MemoryBuffer {
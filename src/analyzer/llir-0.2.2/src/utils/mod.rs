@@ -5,6 +5,7 @@ pub mod mem_buffer;
 pub mod support;
 mod string;
 pub mod mdkind_ids;
+pub mod md_node;
 pub mod traits;
 
 pub use string::*;
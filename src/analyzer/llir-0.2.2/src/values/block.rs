@@ -79,6 +79,7 @@ impl<'ctx> Block<'ctx> {
       match term {
         Instruction::Branch(br) => br.destinations(),
         Instruction::Switch(sw) => sw.destinations(),
+        Instruction::Invoke(inv) => inv.destinations(),
         _ => vec![],
       }
     } else {
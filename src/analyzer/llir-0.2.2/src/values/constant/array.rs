@@ -1,5 +1,9 @@
-use llvm_sys::core::{LLVMGetArrayLength, LLVMGetOperand, LLVMTypeOf};
+use llvm_sys::core::{
+  LLVMGetArrayLength, LLVMGetAsString, LLVMGetElementAsConstant, LLVMGetOperand, LLVMGetValueKind,
+  LLVMIsConstantString, LLVMTypeOf,
+};
 use llvm_sys::prelude::LLVMValueRef;
+use llvm_sys::LLVMValueKind;
 use std::marker::PhantomData;
 
 use super::Constant;
@@ -8,6 +12,8 @@ use crate::values::*;
 use crate::*;
 
 /// [Array constant](https://llvm.org/docs/LangRef.html#complex-constants)
+///
+/// Also covers the constant data arrays, e.g. `c"hello\00"` or `[i32 1, i32 2]`
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct ArrayConstant<'ctx>(LLVMValueRef, PhantomData<&'ctx ()>);
 
@@ -23,9 +29,52 @@ impl<'ctx> ArrayConstant<'ctx> {
 
   /// Get the elements used to construct the array constant
   pub fn elements(&self) -> Vec<Constant<'ctx>> {
-    (0..self.num_elements() as u32)
-      .map(|i| Constant::from_llvm(unsafe { LLVMGetOperand(self.0, i) }))
-      .collect()
+    (0..self.num_elements()).filter_map(|i| self.element(i)).collect()
+  }
+
+  /// Get the element at a given index
+  pub fn element(&self, index: usize) -> Option<Constant<'ctx>> {
+    if index >= self.num_elements() {
+      None
+    } else if self.is_data_array() {
+      Some(Constant::from_llvm(unsafe {
+        LLVMGetElementAsConstant(self.0, index as u32)
+      }))
+    } else {
+      Some(Constant::from_llvm(unsafe { LLVMGetOperand(self.0, index as u32) }))
+    }
+  }
+
+  /// Check if the array is a constant data array of simple elements, e.g. `c"hello\00"`
+  pub fn is_data_array(&self) -> bool {
+    match unsafe { LLVMGetValueKind(self.0) } {
+      LLVMValueKind::LLVMConstantDataArrayValueKind => true,
+      _ => false,
+    }
+  }
+
+  /// Check if the array is a string, i.e. a constant data array of `i8`
+  pub fn is_string(&self) -> bool {
+    self.is_data_array() && unsafe { LLVMIsConstantString(self.0) != 0 }
+  }
+
+  /// Get the bytes of a string array, including the trailing zeros
+  pub fn as_bytes(&self) -> Option<&'ctx [u8]> {
+    if self.is_string() {
+      let mut len = 0;
+      let ptr = unsafe { LLVMGetAsString(self.0, &mut len) };
+      Some(unsafe { std::slice::from_raw_parts(ptr as *const u8, len) })
+    } else {
+      None
+    }
+  }
+
+  /// Get the C string of a string array, i.e. the bytes before the first zero
+  pub fn as_c_string(&self) -> Option<String> {
+    self.as_bytes().map(|bytes| {
+      let len = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+      String::from_utf8_lossy(&bytes[..len]).into_owned()
+    })
   }
 
   /// Get directly the array type
@@ -44,4 +93,4 @@ impl<'ctx> AsConstant<'ctx> for ArrayConstant<'ctx> {
   }
 }
 
-impl_as_operand_for_constant!(ArrayConstant);
\ No newline at end of file
+impl_as_operand_for_constant!(ArrayConstant);
@@ -1,4 +1,4 @@
-use llvm_sys::core::LLVMGetValueKind;
+use llvm_sys::core::{LLVMGetValueKind, LLVMIsNull};
 use llvm_sys::prelude::LLVMValueRef;
 use llvm_sys::LLVMValueKind;
 
@@ -32,6 +32,20 @@ impl<'ctx> Constant<'ctx> {
   pub fn as_operand(&self) -> Operand<'ctx> {
     Operand::Constant(*self)
   }
+
+  /// Get the element at a given index of a struct or array constant
+  pub fn element(&self, index: usize) -> Option<Constant<'ctx>> {
+    match self {
+      Self::Struct(sc) => sc.element(index),
+      Self::Array(ac) => ac.element(index),
+      _ => None,
+    }
+  }
+
+  /// Check if the constant is zero of its type, e.g. `0`, `null` or `zeroinitializer`
+  pub fn is_null_value(&self) -> bool {
+    unsafe { LLVMIsNull(self.value_ref()) != 0 }
+  }
 }
 
 impl<'ctx> GetType<'ctx> for Constant<'ctx> {}
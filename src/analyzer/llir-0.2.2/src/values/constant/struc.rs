@@ -27,6 +27,15 @@ impl<'ctx> StructConstant<'ctx> {
       .collect()
   }
 
+  /// Get the element at a given index
+  pub fn element(&self, index: usize) -> Option<Constant<'ctx>> {
+    if index < self.num_elements() {
+      Some(Constant::from_llvm(unsafe { LLVMGetOperand(self.0, index as u32) }))
+    } else {
+      None
+    }
+  }
+
   /// Get directly the struct type
   pub fn get_struct_type(&self) -> StructType<'ctx> {
     StructType::from_llvm(self.get_type().type_ref())
@@ -1,8 +1,10 @@
 use llvm_sys::core::*;
+use llvm_sys::debuginfo::LLVMGetSubprogram;
 use llvm_sys::prelude::LLVMValueRef;
 use std::marker::PhantomData;
 
 use crate::types::*;
+use crate::utils::md_node::md_as_value;
 use crate::utils::string_of_value;
 use crate::values::*;
 use crate::*;
@@ -29,6 +31,11 @@ impl<'ctx> Function<'ctx> {
     string_of_value(self.0)
   }
 
+  /// Get the module containing this function
+  pub fn parent_module(&self) -> Module<'ctx> {
+    Module::new(unsafe { LLVMGetGlobalParent(self.0) })
+  }
+
   /// Check if this function is declaration only
   pub fn is_declaration_only(&self) -> bool {
     let first_block = unsafe { LLVMGetFirstBasicBlock(self.0) };
@@ -130,6 +137,23 @@ impl<'ctx> Function<'ctx> {
     let type_ref = unsafe { LLVMGetElementType(LLVMTypeOf(self.0)) };
     FunctionType::from_llvm(type_ref)
   }
+
+  /// Get the debug info of the function, if compiled with debug info
+  pub fn subprogram(&self) -> Option<DISubprogram<'ctx>> {
+    md_as_value(self.0, unsafe { LLVMGetSubprogram(self.0) }).map(DISubprogram::from_llvm)
+  }
+
+  /// Get the source variables declared in this function, along with the values
+  /// describing them in `llvm.dbg.declare` or `llvm.dbg.value`, e.g. the allocas
+  pub fn debug_variables(&self) -> Vec<(Operand<'ctx>, DILocalVariable<'ctx>)> {
+    self
+      .iter_instructions()
+      .filter_map(|instr| match instr {
+        Instruction::Call(call) => call.debug_variable(),
+        _ => None,
+      })
+      .collect()
+  }
 }
 
 impl_positional_value_ref!(Function, 0);
@@ -1,9 +1,9 @@
 use llvm_sys::core::*;
 use llvm_sys::prelude::LLVMValueRef;
-use llvm_sys::LLVMValueKind;
+use llvm_sys::{LLVMLinkage, LLVMValueKind};
 use std::marker::PhantomData;
 
-use crate::utils::string_of_value;
+use crate::utils::{raw_to_string, string_of_value};
 use crate::values::*;
 use crate::*;
 
@@ -19,12 +19,85 @@ pub enum Global<'ctx> {
   Alias(GlobalAlias<'ctx>),
 }
 
+/// [Linkage type](https://llvm.org/docs/LangRef.html#linkage-types) of a global
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Linkage {
+  External,
+  AvailableExternally,
+  LinkOnceAny,
+  LinkOnceODR,
+  WeakAny,
+  WeakODR,
+  Appending,
+  Internal,
+  Private,
+  ExternalWeak,
+  Common,
+  Other,
+}
+
+impl Linkage {
+  pub(crate) fn from_llvm(linkage: LLVMLinkage) -> Self {
+    use LLVMLinkage::*;
+    match linkage {
+      LLVMExternalLinkage => Self::External,
+      LLVMAvailableExternallyLinkage => Self::AvailableExternally,
+      LLVMLinkOnceAnyLinkage => Self::LinkOnceAny,
+      LLVMLinkOnceODRLinkage => Self::LinkOnceODR,
+      LLVMWeakAnyLinkage => Self::WeakAny,
+      LLVMWeakODRLinkage => Self::WeakODR,
+      LLVMAppendingLinkage => Self::Appending,
+      LLVMInternalLinkage => Self::Internal,
+      LLVMPrivateLinkage => Self::Private,
+      LLVMExternalWeakLinkage => Self::ExternalWeak,
+      LLVMCommonLinkage => Self::Common,
+      _ => Self::Other,
+    }
+  }
+
+  /// Check if the global is only visible in its module, e.g. `static` in C
+  pub fn is_local(&self) -> bool {
+    match self {
+      Self::Internal | Self::Private => true,
+      _ => false,
+    }
+  }
+
+  /// Check if the definition may be replaced by another one at link time
+  pub fn is_interposable(&self) -> bool {
+    match self {
+      Self::LinkOnceAny | Self::WeakAny | Self::ExternalWeak | Self::Common | Self::Other => true,
+      _ => false,
+    }
+  }
+}
+
 pub trait GlobalValueTrait<'ctx>: ValueRef {
   /// Get the name of this global
   fn name(&self) -> String {
     string_of_value(self.value_ref())
   }
 
+  /// Get the linkage of this global
+  fn linkage(&self) -> Linkage {
+    Linkage::from_llvm(unsafe { LLVMGetLinkage(self.value_ref()) })
+  }
+
+  /// Get the section this global is placed in, if specified
+  fn section(&self) -> Option<String> {
+    let ptr = unsafe { LLVMGetSection(self.value_ref()) };
+    if ptr.is_null() {
+      None
+    } else {
+      let section = unsafe { raw_to_string(ptr) };
+      if section.is_empty() {
+        None
+      } else {
+        Some(section)
+      }
+    }
+  }
+
   /// Global value can be turned into a Global enum
   fn as_global(&self) -> Global<'ctx>;
 }
@@ -106,6 +179,43 @@ impl<'ctx> GlobalValueTrait<'ctx> for GlobalVariable<'ctx> {
   }
 }
 
+impl<'ctx> GlobalVariable<'ctx> {
+  /// Get the initializer of the global variable; `None` if it is only declared
+  pub fn initializer(&self) -> Option<Constant<'ctx>> {
+    let ptr = unsafe { LLVMGetInitializer(self.0) };
+    if ptr.is_null() {
+      None
+    } else {
+      Some(Constant::from_llvm(ptr))
+    }
+  }
+
+  /// Check if the global variable is marked as `constant`
+  pub fn is_constant(&self) -> bool {
+    unsafe { LLVMIsGlobalConstant(self.0) != 0 }
+  }
+
+  /// Check if the global variable is thread local
+  pub fn is_thread_local(&self) -> bool {
+    unsafe { LLVMIsThreadLocal(self.0) != 0 }
+  }
+
+  /// Check if the global variable may be initialized outside of the program
+  pub fn is_externally_initialized(&self) -> bool {
+    unsafe { LLVMIsExternallyInitialized(self.0) != 0 }
+  }
+
+  /// Get the initializer if the value of the global variable is always the initializer, i.e. it is
+  /// constant and its definition cannot be replaced at link time, e.g. a `static const` table
+  pub fn constant_initializer(&self) -> Option<Constant<'ctx>> {
+    if self.is_constant() && !self.is_externally_initialized() && !self.linkage().is_interposable() {
+      self.initializer()
+    } else {
+      None
+    }
+  }
+}
+
 impl_positional_value_ref!(GlobalVariable, 0);
 
 impl_positional_from_llvm_value!(GlobalVariable);
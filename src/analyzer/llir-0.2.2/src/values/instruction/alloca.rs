@@ -1,3 +1,4 @@
+use llvm_sys::core::LLVMGetOperand;
 use llvm_sys::prelude::LLVMValueRef;
 use std::marker::PhantomData;
 
@@ -45,6 +46,21 @@ impl<'ctx> AllocaInstruction<'ctx> {
   pub fn get_element_type(&self) -> Type<'ctx> {
     self.get_pointer_type().element_type()
   }
+
+  /// Get the number of elements allocated, which is the constant `1` unless an array size is given
+  pub fn num_elements(&self) -> Operand<'ctx> {
+    Operand::from_llvm(unsafe { LLVMGetOperand(self.0, 0) })
+  }
+
+  /// Get the source variable stored in this alloca, by `llvm.dbg.declare`
+  pub fn debug_variable(&self) -> Option<DILocalVariable<'ctx>> {
+    let this = self.as_operand();
+    self
+      .parent_function()
+      .debug_variables()
+      .into_iter()
+      .find_map(|(value, var)| if value == this { Some(var) } else { None })
+  }
 }
 
 impl_positional_value_ref!(AllocaInstruction, 0);
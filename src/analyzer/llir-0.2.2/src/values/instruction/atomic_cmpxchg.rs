@@ -0,0 +1,70 @@
+use llvm_sys::core::{LLVMGetOperand, LLVMGetVolatile, LLVMGetWeak};
+use llvm_sys::prelude::LLVMValueRef;
+use std::marker::PhantomData;
+
+use crate::values::*;
+use crate::*;
+
+/// [Atomic compare and exchange instruction](https://llvm.org/docs/LangRef.html#cmpxchg-instruction)
+///
+/// The result is a pair `{ old, success }` of the value loaded and whether it equals the compared one,
+/// in which case the new value is stored
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct AtomicCmpXchgInstruction<'ctx>(LLVMValueRef, PhantomData<&'ctx ()>);
+
+impl_instr_debug!(AtomicCmpXchgInstruction);
+
+impl_as_operand_for_instr!(AtomicCmpXchgInstruction);
+
+impl_send_sync!(AtomicCmpXchgInstruction);
+
+impl<'ctx> GetType<'ctx> for AtomicCmpXchgInstruction<'ctx> {}
+
+impl<'ctx> GetDebugMetadata<'ctx> for AtomicCmpXchgInstruction<'ctx> {}
+
+impl<'ctx> InstructionDebugLoc for AtomicCmpXchgInstruction<'ctx> {}
+
+impl<'ctx> InstructionTrait<'ctx> for AtomicCmpXchgInstruction<'ctx> {}
+
+impl<'ctx> AtomicCmpXchgInstruction<'ctx> {
+  /// Get the location operand which is compared and exchanged
+  pub fn location(&self) -> Operand<'ctx> {
+    Operand::from_llvm(unsafe { LLVMGetOperand(self.0, 0) })
+  }
+
+  /// Get the value compared with the one at the location
+  pub fn compare(&self) -> Operand<'ctx> {
+    Operand::from_llvm(unsafe { LLVMGetOperand(self.0, 1) })
+  }
+
+  /// Get the new value stored if the comparison succeeds
+  pub fn new_value(&self) -> Operand<'ctx> {
+    Operand::from_llvm(unsafe { LLVMGetOperand(self.0, 2) })
+  }
+
+  /// Check if the instruction is weak, i.e. it may fail even if the comparison succeeds
+  pub fn is_weak(&self) -> bool {
+    unsafe { LLVMGetWeak(self.0) != 0 }
+  }
+
+  /// Check if the instruction is volatile
+  pub fn is_volatile(&self) -> bool {
+    unsafe { LLVMGetVolatile(self.0) != 0 }
+  }
+}
+
+impl<'ctx> ValueOpcode for AtomicCmpXchgInstruction<'ctx> {
+  fn opcode(&self) -> Opcode {
+    Opcode::AtomicCmpXchg
+  }
+}
+
+impl<'ctx> AsInstruction<'ctx> for AtomicCmpXchgInstruction<'ctx> {
+  fn as_instruction(&self) -> Instruction<'ctx> {
+    Instruction::AtomicCmpXchg(*self)
+  }
+}
+
+impl_positional_value_ref!(AtomicCmpXchgInstruction, 0);
+
+impl_positional_from_llvm_value!(AtomicCmpXchgInstruction);
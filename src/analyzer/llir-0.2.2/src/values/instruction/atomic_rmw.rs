@@ -0,0 +1,140 @@
+use llvm_sys::core::{LLVMGetAtomicRMWBinOp, LLVMGetOperand, LLVMGetVolatile};
+use llvm_sys::prelude::LLVMValueRef;
+use llvm_sys::LLVMAtomicRMWBinOp;
+use std::marker::PhantomData;
+
+use crate::values::*;
+use crate::*;
+
+/// The operation of an atomic read-modify-write
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AtomicRMWBinaryOpcode {
+  Xchg,
+  Add,
+  Sub,
+  And,
+  Nand,
+  Or,
+  Xor,
+  Max,
+  Min,
+  UMax,
+  UMin,
+  FAdd,
+  FSub,
+  Other,
+}
+
+impl AtomicRMWBinaryOpcode {
+  pub(crate) fn from_llvm(op: LLVMAtomicRMWBinOp) -> Self {
+    use LLVMAtomicRMWBinOp::*;
+    #[allow(unreachable_patterns)]
+    match op {
+      LLVMAtomicRMWBinOpXchg => Self::Xchg,
+      LLVMAtomicRMWBinOpAdd => Self::Add,
+      LLVMAtomicRMWBinOpSub => Self::Sub,
+      LLVMAtomicRMWBinOpAnd => Self::And,
+      LLVMAtomicRMWBinOpNand => Self::Nand,
+      LLVMAtomicRMWBinOpOr => Self::Or,
+      LLVMAtomicRMWBinOpXor => Self::Xor,
+      LLVMAtomicRMWBinOpMax => Self::Max,
+      LLVMAtomicRMWBinOpMin => Self::Min,
+      LLVMAtomicRMWBinOpUMax => Self::UMax,
+      LLVMAtomicRMWBinOpUMin => Self::UMin,
+      LLVMAtomicRMWBinOpFAdd => Self::FAdd,
+      LLVMAtomicRMWBinOpFSub => Self::FSub,
+      _ => Self::Other,
+    }
+  }
+
+  /// Get the binary opcode computing the new value from the old one, if there is one
+  pub fn binary_opcode(&self) -> Option<BinaryOpcode> {
+    match self {
+      Self::Add => Some(BinaryOpcode::Add),
+      Self::Sub => Some(BinaryOpcode::Sub),
+      Self::And => Some(BinaryOpcode::And),
+      Self::Or => Some(BinaryOpcode::Or),
+      Self::Xor => Some(BinaryOpcode::Xor),
+      Self::FAdd => Some(BinaryOpcode::FAdd),
+      Self::FSub => Some(BinaryOpcode::FSub),
+      _ => None,
+    }
+  }
+
+  pub fn to_string(&self) -> &str {
+    match self {
+      Self::Xchg => "xchg",
+      Self::Add => "add",
+      Self::Sub => "sub",
+      Self::And => "and",
+      Self::Nand => "nand",
+      Self::Or => "or",
+      Self::Xor => "xor",
+      Self::Max => "max",
+      Self::Min => "min",
+      Self::UMax => "umax",
+      Self::UMin => "umin",
+      Self::FAdd => "fadd",
+      Self::FSub => "fsub",
+      Self::Other => "other",
+    }
+  }
+}
+
+/// [Atomic read-modify-write instruction](https://llvm.org/docs/LangRef.html#atomicrmw-instruction)
+///
+/// The result is the value at the location before the modification
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct AtomicRMWInstruction<'ctx>(LLVMValueRef, PhantomData<&'ctx ()>);
+
+impl_instr_debug!(AtomicRMWInstruction);
+
+impl_as_operand_for_instr!(AtomicRMWInstruction);
+
+impl_send_sync!(AtomicRMWInstruction);
+
+impl<'ctx> GetType<'ctx> for AtomicRMWInstruction<'ctx> {}
+
+impl<'ctx> GetDebugMetadata<'ctx> for AtomicRMWInstruction<'ctx> {}
+
+impl<'ctx> InstructionDebugLoc for AtomicRMWInstruction<'ctx> {}
+
+impl<'ctx> InstructionTrait<'ctx> for AtomicRMWInstruction<'ctx> {}
+
+impl<'ctx> AtomicRMWInstruction<'ctx> {
+  /// Get the operation
+  pub fn binary_opcode(&self) -> AtomicRMWBinaryOpcode {
+    AtomicRMWBinaryOpcode::from_llvm(unsafe { LLVMGetAtomicRMWBinOp(self.0) })
+  }
+
+  /// Get the location operand which is modified
+  pub fn location(&self) -> Operand<'ctx> {
+    Operand::from_llvm(unsafe { LLVMGetOperand(self.0, 0) })
+  }
+
+  /// Get the value operand of the operation
+  pub fn value(&self) -> Operand<'ctx> {
+    Operand::from_llvm(unsafe { LLVMGetOperand(self.0, 1) })
+  }
+
+  /// Check if the instruction is volatile
+  pub fn is_volatile(&self) -> bool {
+    unsafe { LLVMGetVolatile(self.0) != 0 }
+  }
+}
+
+impl<'ctx> ValueOpcode for AtomicRMWInstruction<'ctx> {
+  fn opcode(&self) -> Opcode {
+    Opcode::AtomicRMW
+  }
+}
+
+impl<'ctx> AsInstruction<'ctx> for AtomicRMWInstruction<'ctx> {
+  fn as_instruction(&self) -> Instruction<'ctx> {
+    Instruction::AtomicRMW(*self)
+  }
+}
+
+impl_positional_value_ref!(AtomicRMWInstruction, 0);
+
+impl_positional_from_llvm_value!(AtomicRMWInstruction);
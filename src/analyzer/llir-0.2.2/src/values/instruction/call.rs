@@ -91,6 +91,31 @@ impl<'ctx> CallInstruction<'ctx> {
   pub fn is_intrinsic_call(&self) -> bool {
     unsafe { !LLVMIsAIntrinsicInst(self.0).is_null() }
   }
+
+  /// Check if this call is to `llvm.dbg.declare`, `llvm.dbg.value` or `llvm.dbg.addr`
+  pub fn is_debug_intrinsic_call(&self) -> bool {
+    match self.callee_function() {
+      Some(f) => self.is_intrinsic_call() && f.name().starts_with("llvm.dbg."),
+      None => false,
+    }
+  }
+
+  /// Get the value and the source variable it describes if this is a call to
+  /// `llvm.dbg.declare`, `llvm.dbg.value` or `llvm.dbg.addr`
+  pub fn debug_variable(&self) -> Option<(Operand<'ctx>, DILocalVariable<'ctx>)> {
+    if !self.is_debug_intrinsic_call() {
+      return None;
+    }
+    let value = match self.argument(0)? {
+      Operand::Metadata(Metadata::LocalAsMetadata(lam)) => lam.value()?,
+      Operand::Metadata(Metadata::ConstantAsMetadata(cam)) => cam.value()?,
+      _ => return None,
+    };
+    match self.argument(1)? {
+      Operand::Metadata(Metadata::DILocalVariable(var)) => Some((value, var)),
+      _ => None,
+    }
+  }
 }
 
 impl<'ctx> ValueOpcode for CallInstruction<'ctx> {
@@ -0,0 +1,147 @@
+use llvm_sys::prelude::LLVMValueRef;
+
+use crate::types::*;
+use crate::values::*;
+use crate::*;
+
+/// A call site, either a [call](struct.CallInstruction.html) or an
+/// [invoke](struct.InvokeInstruction.html)
+///
+/// Mirrors LLVM's `CallBase` so that call graph and analyses can treat both the same way
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CallBaseInstruction<'ctx> {
+  Call(CallInstruction<'ctx>),
+  Invoke(InvokeInstruction<'ctx>),
+}
+
+impl_as_operand_for_instr!(CallBaseInstruction);
+
+impl<'ctx> GetType<'ctx> for CallBaseInstruction<'ctx> {}
+
+impl<'ctx> GetDebugMetadata<'ctx> for CallBaseInstruction<'ctx> {}
+
+impl<'ctx> InstructionDebugLoc for CallBaseInstruction<'ctx> {}
+
+impl<'ctx> InstructionTrait<'ctx> for CallBaseInstruction<'ctx> {}
+
+impl<'ctx> AsInstruction<'ctx> for CallBaseInstruction<'ctx> {
+  fn as_instruction(&self) -> Instruction<'ctx> {
+    match self {
+      Self::Call(c) => c.as_instruction(),
+      Self::Invoke(i) => i.as_instruction(),
+    }
+  }
+}
+
+impl<'ctx> ValueOpcode for CallBaseInstruction<'ctx> {
+  fn opcode(&self) -> Opcode {
+    match self {
+      Self::Call(c) => c.opcode(),
+      Self::Invoke(i) => i.opcode(),
+    }
+  }
+}
+
+impl<'ctx> CallBaseInstruction<'ctx> {
+  /// Get the call site from an instruction if it is a call or an invoke
+  pub fn from_instruction(instr: Instruction<'ctx>) -> Option<Self> {
+    match instr {
+      Instruction::Call(c) => Some(Self::Call(c)),
+      Instruction::Invoke(i) => Some(Self::Invoke(i)),
+      _ => None,
+    }
+  }
+
+  /// Get the callee function if the callee is an LLVM function
+  pub fn callee_function(&self) -> Option<Function<'ctx>> {
+    match self {
+      Self::Call(c) => c.callee_function(),
+      Self::Invoke(i) => i.callee_function(),
+    }
+  }
+
+  /// Get the callee as inline assembly value if the callee is an InlineAsm
+  pub fn callee_inline_asm(&self) -> Option<InlineAsm<'ctx>> {
+    match self {
+      Self::Call(c) => c.callee_inline_asm(),
+      Self::Invoke(i) => i.callee_inline_asm(),
+    }
+  }
+
+  /// Get the callee function type
+  pub fn callee_function_type(&self) -> FunctionType<'ctx> {
+    match self {
+      Self::Call(c) => c.callee_function_type(),
+      Self::Invoke(i) => i.callee_function_type(),
+    }
+  }
+
+  /// Get the callee value in operand
+  pub fn callee(&self) -> Operand<'ctx> {
+    match self {
+      Self::Call(c) => c.callee(),
+      Self::Invoke(i) => i.callee(),
+    }
+  }
+
+  /// Get the number of arguments passed to the callee function
+  pub fn num_arguments(&self) -> usize {
+    match self {
+      Self::Call(c) => c.num_arguments(),
+      Self::Invoke(i) => i.num_arguments(),
+    }
+  }
+
+  /// Get the arguments being passed to the callee function
+  pub fn arguments(&self) -> Vec<Operand<'ctx>> {
+    match self {
+      Self::Call(c) => c.arguments(),
+      Self::Invoke(i) => i.arguments(),
+    }
+  }
+
+  /// Get the argument at a given index
+  pub fn argument(&self, index: usize) -> Option<Operand<'ctx>> {
+    match self {
+      Self::Call(c) => c.argument(index),
+      Self::Invoke(i) => i.argument(index),
+    }
+  }
+
+  /// Check if this call site is to an inline assembly
+  pub fn is_inline_asm_call(&self) -> bool {
+    match self {
+      Self::Call(c) => c.is_inline_asm_call(),
+      Self::Invoke(i) => i.is_inline_asm_call(),
+    }
+  }
+
+  /// Check if this call site is to llvm intrinsic function
+  pub fn is_intrinsic_call(&self) -> bool {
+    match self {
+      Self::Call(c) => c.is_intrinsic_call(),
+      Self::Invoke(i) => i.is_intrinsic_call(),
+    }
+  }
+}
+
+impl<'ctx> From<CallInstruction<'ctx>> for CallBaseInstruction<'ctx> {
+  fn from(c: CallInstruction<'ctx>) -> Self {
+    Self::Call(c)
+  }
+}
+
+impl<'ctx> From<InvokeInstruction<'ctx>> for CallBaseInstruction<'ctx> {
+  fn from(i: InvokeInstruction<'ctx>) -> Self {
+    Self::Invoke(i)
+  }
+}
+
+impl<'ctx> ValueRef for CallBaseInstruction<'ctx> {
+  fn value_ref(&self) -> LLVMValueRef {
+    match self {
+      Self::Call(c) => c.value_ref(),
+      Self::Invoke(i) => i.value_ref(),
+    }
+  }
+}
@@ -0,0 +1,54 @@
+use llvm_sys::core::{LLVMGetArgOperand, LLVMGetNumArgOperands};
+use llvm_sys::prelude::LLVMValueRef;
+use std::marker::PhantomData;
+
+use crate::values::*;
+use crate::*;
+
+/// [CleanupPad instruction](https://llvm.org/docs/LangRef.html#cleanuppad-instruction)
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CleanupPadInstruction<'ctx>(LLVMValueRef, PhantomData<&'ctx ()>);
+
+impl_instr_debug!(CleanupPadInstruction);
+
+impl_as_operand_for_instr!(CleanupPadInstruction);
+
+impl_send_sync!(CleanupPadInstruction);
+
+impl<'ctx> GetType<'ctx> for CleanupPadInstruction<'ctx> {}
+
+impl<'ctx> GetDebugMetadata<'ctx> for CleanupPadInstruction<'ctx> {}
+
+impl<'ctx> InstructionDebugLoc for CleanupPadInstruction<'ctx> {}
+
+impl<'ctx> InstructionTrait<'ctx> for CleanupPadInstruction<'ctx> {}
+
+impl<'ctx> CleanupPadInstruction<'ctx> {
+  /// Get the number of arguments of the funclet pad
+  pub fn num_arguments(&self) -> usize {
+    unsafe { LLVMGetNumArgOperands(self.0) as usize }
+  }
+
+  /// Get the arguments of the funclet pad
+  pub fn arguments(&self) -> Vec<Operand<'ctx>> {
+    (0..self.num_arguments())
+      .map(|i| Operand::from_llvm(unsafe { LLVMGetArgOperand(self.0, i as u32) }))
+      .collect()
+  }
+}
+
+impl<'ctx> ValueOpcode for CleanupPadInstruction<'ctx> {
+  fn opcode(&self) -> Opcode {
+    Opcode::CleanupPad
+  }
+}
+
+impl<'ctx> AsInstruction<'ctx> for CleanupPadInstruction<'ctx> {
+  fn as_instruction(&self) -> Instruction<'ctx> {
+    Instruction::CleanupPad(*self)
+  }
+}
+
+impl_positional_value_ref!(CleanupPadInstruction, 0);
+
+impl_positional_from_llvm_value!(CleanupPadInstruction);
@@ -0,0 +1,45 @@
+use llvm_sys::core::LLVMIsAtomicSingleThread;
+use llvm_sys::prelude::LLVMValueRef;
+use std::marker::PhantomData;
+
+use crate::values::*;
+use crate::*;
+
+/// [Fence instruction](https://llvm.org/docs/LangRef.html#fence-instruction)
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FenceInstruction<'ctx>(LLVMValueRef, PhantomData<&'ctx ()>);
+
+impl_instr_debug!(FenceInstruction);
+
+impl_as_operand_for_instr!(FenceInstruction);
+
+impl_send_sync!(FenceInstruction);
+
+impl<'ctx> GetDebugMetadata<'ctx> for FenceInstruction<'ctx> {}
+
+impl<'ctx> InstructionDebugLoc for FenceInstruction<'ctx> {}
+
+impl<'ctx> InstructionTrait<'ctx> for FenceInstruction<'ctx> {}
+
+impl<'ctx> FenceInstruction<'ctx> {
+  /// Check if the fence only synchronizes with the same thread, e.g. signal handlers
+  pub fn is_single_thread(&self) -> bool {
+    unsafe { LLVMIsAtomicSingleThread(self.0) != 0 }
+  }
+}
+
+impl<'ctx> ValueOpcode for FenceInstruction<'ctx> {
+  fn opcode(&self) -> Opcode {
+    Opcode::Fence
+  }
+}
+
+impl<'ctx> AsInstruction<'ctx> for FenceInstruction<'ctx> {
+  fn as_instruction(&self) -> Instruction<'ctx> {
+    Instruction::Fence(*self)
+  }
+}
+
+impl_positional_value_ref!(FenceInstruction, 0);
+
+impl_positional_from_llvm_value!(FenceInstruction);
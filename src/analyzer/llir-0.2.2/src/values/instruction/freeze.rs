@@ -0,0 +1,49 @@
+use llvm_sys::core::LLVMGetOperand;
+use llvm_sys::prelude::LLVMValueRef;
+use std::marker::PhantomData;
+
+use crate::values::*;
+use crate::*;
+
+/// [Freeze instruction](https://llvm.org/docs/LangRef.html#freeze-instruction)
+///
+/// The result is the operand itself, or an arbitrary fixed value if the operand is undef or poison
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FreezeInstruction<'ctx>(LLVMValueRef, PhantomData<&'ctx ()>);
+
+impl_instr_debug!(FreezeInstruction);
+
+impl_as_operand_for_instr!(FreezeInstruction);
+
+impl_send_sync!(FreezeInstruction);
+
+impl<'ctx> GetType<'ctx> for FreezeInstruction<'ctx> {}
+
+impl<'ctx> GetDebugMetadata<'ctx> for FreezeInstruction<'ctx> {}
+
+impl<'ctx> InstructionDebugLoc for FreezeInstruction<'ctx> {}
+
+impl<'ctx> InstructionTrait<'ctx> for FreezeInstruction<'ctx> {}
+
+impl<'ctx> FreezeInstruction<'ctx> {
+  /// Get the operand being frozen
+  pub fn op0(&self) -> Operand<'ctx> {
+    Operand::from_llvm(unsafe { LLVMGetOperand(self.0, 0) })
+  }
+}
+
+impl<'ctx> ValueOpcode for FreezeInstruction<'ctx> {
+  fn opcode(&self) -> Opcode {
+    Opcode::Freeze
+  }
+}
+
+impl<'ctx> AsInstruction<'ctx> for FreezeInstruction<'ctx> {
+  fn as_instruction(&self) -> Instruction<'ctx> {
+    Instruction::Freeze(*self)
+  }
+}
+
+impl_positional_value_ref!(FreezeInstruction, 0);
+
+impl_positional_from_llvm_value!(FreezeInstruction);
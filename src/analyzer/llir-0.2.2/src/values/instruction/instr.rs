@@ -9,24 +9,33 @@ use crate::*;
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Instruction<'ctx> {
   Alloca(AllocaInstruction<'ctx>),
+  AtomicCmpXchg(AtomicCmpXchgInstruction<'ctx>),
+  AtomicRMW(AtomicRMWInstruction<'ctx>),
   Binary(BinaryInstruction<'ctx>),
   Branch(BranchInstruction<'ctx>),
   Call(CallInstruction<'ctx>),
   CallBr(CallBrInstruction<'ctx>),
+  CleanupPad(CleanupPadInstruction<'ctx>),
   ExtractValue(ExtractValueInstruction<'ctx>),
   FCmp(FCmpInstruction<'ctx>),
+  Fence(FenceInstruction<'ctx>),
+  Freeze(FreezeInstruction<'ctx>),
   GetElementPtr(GetElementPtrInstruction<'ctx>),
   ICmp(ICmpInstruction<'ctx>),
   IndirectBranch(IndirectBranchInstruction<'ctx>),
   InsertValue(InsertValueInstruction<'ctx>),
+  Invoke(InvokeInstruction<'ctx>),
+  LandingPad(LandingPadInstruction<'ctx>),
   Load(LoadInstruction<'ctx>),
   Phi(PhiInstruction<'ctx>),
+  Resume(ResumeInstruction<'ctx>),
   Return(ReturnInstruction<'ctx>),
   Select(SelectInstruction<'ctx>),
   Store(StoreInstruction<'ctx>),
   Switch(SwitchInstruction<'ctx>),
   Unary(UnaryInstruction<'ctx>),
   Unreachable(UnreachableInstruction<'ctx>),
+  VAArg(VAArgInstruction<'ctx>),
   Other(GenericValue<'ctx>),
 }
 
@@ -40,24 +49,33 @@ impl<'ctx> ValueOpcode for Instruction<'ctx> {
   fn opcode(&self) -> Opcode {
     match self {
       Self::Alloca(alc_instr) => alc_instr.opcode(),
+      Self::AtomicCmpXchg(cmpxchg_instr) => cmpxchg_instr.opcode(),
+      Self::AtomicRMW(rmw_instr) => rmw_instr.opcode(),
       Self::Binary(bin_instr) => bin_instr.opcode(),
       Self::Branch(br_instr) => br_instr.opcode(),
       Self::Call(call_instr) => call_instr.opcode(),
       Self::CallBr(call_br_instr) => call_br_instr.opcode(),
+      Self::CleanupPad(clp_instr) => clp_instr.opcode(),
       Self::ExtractValue(extval_instr) => extval_instr.opcode(),
       Self::FCmp(fcmp_instr) => fcmp_instr.opcode(),
+      Self::Fence(fence_instr) => fence_instr.opcode(),
+      Self::Freeze(frz_instr) => frz_instr.opcode(),
       Self::GetElementPtr(gep_instr) => gep_instr.opcode(),
       Self::ICmp(icmp_instr) => icmp_instr.opcode(),
       Self::IndirectBranch(indbr_instr) => indbr_instr.opcode(),
       Self::InsertValue(insval_instr) => insval_instr.opcode(),
+      Self::Invoke(invoke_instr) => invoke_instr.opcode(),
+      Self::LandingPad(ldp_instr) => ldp_instr.opcode(),
       Self::Load(ld_instr) => ld_instr.opcode(),
       Self::Phi(phi_instr) => phi_instr.opcode(),
+      Self::Resume(res_instr) => res_instr.opcode(),
       Self::Return(ret_instr) => ret_instr.opcode(),
       Self::Select(sel_instr) => sel_instr.opcode(),
       Self::Store(st_instr) => st_instr.opcode(),
       Self::Switch(switch_instr) => switch_instr.opcode(),
       Self::Unary(una_instr) => una_instr.opcode(),
       Self::Unreachable(unr_instr) => unr_instr.opcode(),
+      Self::VAArg(va_arg_instr) => va_arg_instr.opcode(),
       Self::Other(_) => Opcode::Unknown,
     }
   }
@@ -76,21 +94,30 @@ impl<'ctx> FromLLVMValue for Instruction<'ctx> {
     use LLVMOpcode::*;
     match unsafe { LLVMGetInstructionOpcode(ptr) } {
       LLVMAlloca => Self::Alloca(AllocaInstruction::from_llvm(ptr)),
+      LLVMAtomicCmpXchg => Self::AtomicCmpXchg(AtomicCmpXchgInstruction::from_llvm(ptr)),
+      LLVMAtomicRMW => Self::AtomicRMW(AtomicRMWInstruction::from_llvm(ptr)),
       LLVMBr => Self::Branch(BranchInstruction::from_llvm(ptr)),
       LLVMCall => Self::Call(CallInstruction::from_llvm(ptr)),
       LLVMCallBr => Self::CallBr(CallBrInstruction::from_llvm(ptr)),
+      LLVMCleanupPad => Self::CleanupPad(CleanupPadInstruction::from_llvm(ptr)),
       LLVMExtractValue => Self::ExtractValue(ExtractValueInstruction::from_llvm(ptr)),
       LLVMFCmp => Self::FCmp(FCmpInstruction::from_llvm(ptr)),
+      LLVMFence => Self::Fence(FenceInstruction::from_llvm(ptr)),
+      LLVMFreeze => Self::Freeze(FreezeInstruction::from_llvm(ptr)),
       LLVMGetElementPtr => Self::GetElementPtr(GetElementPtrInstruction::from_llvm(ptr)),
       LLVMICmp => Self::ICmp(ICmpInstruction::from_llvm(ptr)),
       LLVMIndirectBr => Self::IndirectBranch(IndirectBranchInstruction::from_llvm(ptr)),
+      LLVMInvoke => Self::Invoke(InvokeInstruction::from_llvm(ptr)),
+      LLVMLandingPad => Self::LandingPad(LandingPadInstruction::from_llvm(ptr)),
       LLVMLoad => Self::Load(LoadInstruction::from_llvm(ptr)),
       LLVMPHI => Self::Phi(PhiInstruction::from_llvm(ptr)),
+      LLVMResume => Self::Resume(ResumeInstruction::from_llvm(ptr)),
       LLVMRet => Self::Return(ReturnInstruction::from_llvm(ptr)),
       LLVMSelect => Self::Select(SelectInstruction::from_llvm(ptr)),
       LLVMStore => Self::Store(StoreInstruction::from_llvm(ptr)),
       LLVMSwitch => Self::Switch(SwitchInstruction::from_llvm(ptr)),
       LLVMUnreachable => Self::Unreachable(UnreachableInstruction::from_llvm(ptr)),
+      LLVMVAArg => Self::VAArg(VAArgInstruction::from_llvm(ptr)),
       op if BinaryOpcode::from_llvm(op).is_some() => Self::Binary(BinaryInstruction::from_llvm(ptr)),
       op if UnaryOpcode::from_llvm(op).is_some() => Self::Unary(UnaryInstruction::from_llvm(ptr)),
       _ => Self::Other(GenericValue::from_llvm(ptr)),
@@ -102,24 +129,33 @@ impl<'ctx> ValueRef for Instruction<'ctx> {
   fn value_ref(&self) -> LLVMValueRef {
     match self {
       Self::Alloca(alc_instr) => alc_instr.value_ref(),
+      Self::AtomicCmpXchg(cmpxchg_instr) => cmpxchg_instr.value_ref(),
+      Self::AtomicRMW(rmw_instr) => rmw_instr.value_ref(),
       Self::Binary(bin_instr) => bin_instr.value_ref(),
       Self::Branch(br_instr) => br_instr.value_ref(),
       Self::Call(call_instr) => call_instr.value_ref(),
       Self::CallBr(call_br_instr) => call_br_instr.value_ref(),
+      Self::CleanupPad(clp_instr) => clp_instr.value_ref(),
       Self::ExtractValue(extval_instr) => extval_instr.value_ref(),
       Self::FCmp(fcmp_instr) => fcmp_instr.value_ref(),
+      Self::Fence(fence_instr) => fence_instr.value_ref(),
+      Self::Freeze(frz_instr) => frz_instr.value_ref(),
       Self::GetElementPtr(gep_instr) => gep_instr.value_ref(),
       Self::ICmp(icmp_instr) => icmp_instr.value_ref(),
       Self::IndirectBranch(indbr_instr) => indbr_instr.value_ref(),
       Self::InsertValue(insval_instr) => insval_instr.value_ref(),
+      Self::Invoke(invoke_instr) => invoke_instr.value_ref(),
+      Self::LandingPad(ldp_instr) => ldp_instr.value_ref(),
       Self::Load(ld_instr) => ld_instr.value_ref(),
       Self::Phi(phi_instr) => phi_instr.value_ref(),
+      Self::Resume(res_instr) => res_instr.value_ref(),
       Self::Return(ret_instr) => ret_instr.value_ref(),
       Self::Select(sel_instr) => sel_instr.value_ref(),
       Self::Store(st_instr) => st_instr.value_ref(),
       Self::Switch(switch_instr) => switch_instr.value_ref(),
       Self::Unary(una_instr) => una_instr.value_ref(),
       Self::Unreachable(unr_instr) => unr_instr.value_ref(),
+      Self::VAArg(va_arg_instr) => va_arg_instr.value_ref(),
       Self::Other(otr_instr) => otr_instr.value_ref(),
     }
   }
@@ -0,0 +1,125 @@
+use llvm_sys::core::*;
+use llvm_sys::prelude::LLVMValueRef;
+use std::marker::PhantomData;
+
+use crate::types::*;
+use crate::values::*;
+use crate::*;
+
+/// [Invoke instruction](https://llvm.org/docs/LangRef.html#invoke-instruction)
+///
+/// An invoke is a call that terminates its block. Control goes to the normal
+/// destination when the callee returns, and to the unwind destination (which
+/// starts with a `landingpad`) when the callee throws.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct InvokeInstruction<'ctx>(LLVMValueRef, PhantomData<&'ctx ()>);
+
+impl_instr_debug!(InvokeInstruction);
+
+impl_as_operand_for_instr!(InvokeInstruction);
+
+impl_send_sync!(InvokeInstruction);
+
+impl<'ctx> GetType<'ctx> for InvokeInstruction<'ctx> {}
+
+impl<'ctx> GetDebugMetadata<'ctx> for InvokeInstruction<'ctx> {}
+
+impl<'ctx> InstructionDebugLoc for InvokeInstruction<'ctx> {}
+
+impl<'ctx> InstructionTrait<'ctx> for InvokeInstruction<'ctx> {}
+
+impl<'ctx> InvokeInstruction<'ctx> {
+  /// Get the callee function if the callee is an LLVM function
+  pub fn callee_function(&self) -> Option<Function<'ctx>> {
+    match self.callee() {
+      Operand::Constant(Constant::Function(f)) => Some(f),
+      _ => None,
+    }
+  }
+
+  /// Get the callee as inline assembly value if the callee is an InlineAsm
+  pub fn callee_inline_asm(&self) -> Option<InlineAsm<'ctx>> {
+    match self.callee() {
+      Operand::InlineAsm(ia) => Some(ia),
+      _ => None,
+    }
+  }
+
+  /// Get the callee function type
+  pub fn callee_function_type(&self) -> FunctionType<'ctx> {
+    FunctionType::from_llvm(unsafe { LLVMGetElementType(self.callee().get_type().type_ref()) })
+  }
+
+  /// Get the callee value in operand
+  pub fn callee(&self) -> Operand<'ctx> {
+    Operand::from_llvm(unsafe { LLVMGetCalledValue(self.0) })
+  }
+
+  /// Get the number of arguments passed to the callee function
+  pub fn num_arguments(&self) -> usize {
+    unsafe { LLVMGetNumArgOperands(self.0) as usize }
+  }
+
+  /// Get the arguments being passed to the callee function
+  pub fn arguments(&self) -> Vec<Operand<'ctx>> {
+    (0..self.num_arguments())
+      .map(|i| Operand::from_llvm(unsafe { LLVMGetOperand(self.0, i as u32) }))
+      .collect()
+  }
+
+  /// Get the argument at a given index
+  pub fn argument(&self, index: usize) -> Option<Operand<'ctx>> {
+    if index < self.num_arguments() {
+      Some(Operand::from_llvm(unsafe { LLVMGetOperand(self.0, index as u32) }))
+    } else {
+      None
+    }
+  }
+
+  /// The block to go to when the callee returns normally
+  pub fn normal_destination(&self) -> Block<'ctx> {
+    Block::from_llvm(unsafe { LLVMGetNormalDest(self.0) })
+  }
+
+  /// The block to go to when the callee throws, starting with a landing pad
+  pub fn unwind_destination(&self) -> Block<'ctx> {
+    Block::from_llvm(unsafe { LLVMGetUnwindDest(self.0) })
+  }
+
+  /// Get the destination blocks, normal destination first
+  pub fn destinations(&self) -> Vec<Block<'ctx>> {
+    vec![self.normal_destination(), self.unwind_destination()]
+  }
+
+  /// Check if this invoke is to an inline assembly
+  pub fn is_inline_asm_call(&self) -> bool {
+    match self.callee() {
+      Operand::InlineAsm(_) => true,
+      _ => false,
+    }
+  }
+
+  /// Check if this invoke is to llvm intrinsic function
+  pub fn is_intrinsic_call(&self) -> bool {
+    match self.callee_function() {
+      Some(f) => f.name().starts_with("llvm."),
+      None => false,
+    }
+  }
+}
+
+impl<'ctx> ValueOpcode for InvokeInstruction<'ctx> {
+  fn opcode(&self) -> Opcode {
+    Opcode::Invoke
+  }
+}
+
+impl<'ctx> AsInstruction<'ctx> for InvokeInstruction<'ctx> {
+  fn as_instruction(&self) -> Instruction<'ctx> {
+    Instruction::Invoke(*self)
+  }
+}
+
+impl_positional_value_ref!(InvokeInstruction, 0);
+
+impl_positional_from_llvm_value!(InvokeInstruction);
@@ -0,0 +1,54 @@
+use llvm_sys::core::{LLVMGetNumClauses, LLVMIsCleanup};
+use llvm_sys::prelude::LLVMValueRef;
+use std::marker::PhantomData;
+
+use crate::values::*;
+use crate::*;
+
+/// [LandingPad instruction](https://llvm.org/docs/LangRef.html#landingpad-instruction)
+///
+/// The first instruction of the unwind destination of an invoke
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LandingPadInstruction<'ctx>(LLVMValueRef, PhantomData<&'ctx ()>);
+
+impl_instr_debug!(LandingPadInstruction);
+
+impl_as_operand_for_instr!(LandingPadInstruction);
+
+impl_send_sync!(LandingPadInstruction);
+
+impl<'ctx> GetType<'ctx> for LandingPadInstruction<'ctx> {}
+
+impl<'ctx> GetDebugMetadata<'ctx> for LandingPadInstruction<'ctx> {}
+
+impl<'ctx> InstructionDebugLoc for LandingPadInstruction<'ctx> {}
+
+impl<'ctx> InstructionTrait<'ctx> for LandingPadInstruction<'ctx> {}
+
+impl<'ctx> LandingPadInstruction<'ctx> {
+  /// Whether the landing pad is a cleanup, i.e., it is entered even if no clause matches
+  pub fn is_cleanup(&self) -> bool {
+    unsafe { LLVMIsCleanup(self.0) != 0 }
+  }
+
+  /// The number of catch/filter clauses
+  pub fn num_clauses(&self) -> usize {
+    unsafe { LLVMGetNumClauses(self.0) as usize }
+  }
+}
+
+impl<'ctx> ValueOpcode for LandingPadInstruction<'ctx> {
+  fn opcode(&self) -> Opcode {
+    Opcode::LandingPad
+  }
+}
+
+impl<'ctx> AsInstruction<'ctx> for LandingPadInstruction<'ctx> {
+  fn as_instruction(&self) -> Instruction<'ctx> {
+    Instruction::LandingPad(*self)
+  }
+}
+
+impl_positional_value_ref!(LandingPadInstruction, 0);
+
+impl_positional_from_llvm_value!(LandingPadInstruction);
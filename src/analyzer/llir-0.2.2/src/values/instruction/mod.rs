@@ -6,45 +6,65 @@ mod opcode;
 
 mod traits;
 mod alloca;
+mod atomic_cmpxchg;
+mod atomic_rmw;
 mod binary;
 mod br;
 mod call;
+mod call_base;
 mod call_br;
+mod cleanup_pad;
 mod extract_value;
 mod insert_value;
 mod icmp;
 mod indir_br;
+mod invoke;
+mod landing_pad;
 mod fcmp;
+mod fence;
+mod freeze;
 mod gep;
 mod instr;
 mod load;
 mod phi;
+mod resume;
 mod ret;
 mod select;
 mod store;
 mod switch;
 mod unary;
 mod unreachable;
+mod va_arg;
 
 pub use opcode::*;
 pub use traits::*;
 pub use alloca::*;
+pub use atomic_cmpxchg::*;
+pub use atomic_rmw::*;
 pub use binary::*;
 pub use br::*;
 pub use call::*;
+pub use call_base::*;
 pub use call_br::*;
+pub use cleanup_pad::*;
 pub use extract_value::*;
 pub use insert_value::*;
 pub use icmp::*;
 pub use indir_br::*;
+pub use invoke::*;
+pub use landing_pad::*;
 pub use fcmp::*;
+pub use fence::*;
+pub use freeze::*;
 pub use gep::*;
 pub use instr::*;
 pub use load::*;
 pub use phi::*;
+pub use resume::*;
 pub use ret::*;
 pub use select::*;
 pub use store::*;
 pub use switch::*;
 pub use unary::*;
-pub use unreachable::*;
\ No newline at end of file
+pub use unreachable::*;
+pub use va_arg::*;
\ No newline at end of file
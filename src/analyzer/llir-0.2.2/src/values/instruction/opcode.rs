@@ -4,24 +4,33 @@ use super::unary::UnaryOpcode;
 #[derive(Debug, Copy, Clone)]
 pub enum Opcode {
   Alloca,
+  AtomicCmpXchg,
+  AtomicRMW,
   Binary(BinaryOpcode),
   Br,
   CallBr,
   Call,
+  CleanupPad,
   ExtractValue,
   FCmp,
+  Fence,
+  Freeze,
   GetElementPtr,
   ICmp,
   IndirectBr,
   InsertValue,
+  Invoke,
+  LandingPad,
   Load,
   Phi,
+  Resume,
   Ret,
   Select,
   Store,
   Switch,
   Unary(UnaryOpcode),
   Unreachable,
+  VAArg,
   Unknown,
 }
 
@@ -29,24 +38,33 @@ impl Opcode {
   pub fn to_string(&self) -> &str {
     match self {
       Self::Alloca => "alloca",
+      Self::AtomicCmpXchg => "cmpxchg",
+      Self::AtomicRMW => "atomicrmw",
       Self::Binary(bin_op) => bin_op.to_string(),
       Self::Br => "br",
       Self::CallBr => "callbr",
       Self::Call => "call",
+      Self::CleanupPad => "cleanuppad",
       Self::ExtractValue => "extractvalue",
       Self::FCmp => "fcmp",
+      Self::Fence => "fence",
+      Self::Freeze => "freeze",
       Self::GetElementPtr => "getelementptr",
       Self::ICmp => "icmp",
       Self::IndirectBr => "indirectbr",
       Self::InsertValue => "insertvalue",
+      Self::Invoke => "invoke",
+      Self::LandingPad => "landingpad",
       Self::Load => "load",
       Self::Phi => "phi",
+      Self::Resume => "resume",
       Self::Ret => "ret",
       Self::Select => "select",
       Self::Store => "store",
       Self::Switch => "switch",
       Self::Unary(una_op) => una_op.to_string(),
       Self::Unreachable => "unreachable",
+      Self::VAArg => "va_arg",
       Self::Unknown => "unknown",
     }
   }
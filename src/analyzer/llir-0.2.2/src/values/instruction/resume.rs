@@ -0,0 +1,47 @@
+use llvm_sys::core::LLVMGetOperand;
+use llvm_sys::prelude::LLVMValueRef;
+use std::marker::PhantomData;
+
+use crate::values::*;
+use crate::*;
+
+/// [Resume instruction](https://llvm.org/docs/LangRef.html#resume-instruction)
+///
+/// Resume propagating an in-flight exception to the caller
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ResumeInstruction<'ctx>(LLVMValueRef, PhantomData<&'ctx ()>);
+
+impl_instr_debug!(ResumeInstruction);
+
+impl_as_operand_for_instr!(ResumeInstruction);
+
+impl_send_sync!(ResumeInstruction);
+
+impl<'ctx> GetDebugMetadata<'ctx> for ResumeInstruction<'ctx> {}
+
+impl<'ctx> InstructionDebugLoc for ResumeInstruction<'ctx> {}
+
+impl<'ctx> InstructionTrait<'ctx> for ResumeInstruction<'ctx> {}
+
+impl<'ctx> ResumeInstruction<'ctx> {
+  /// The exception value being resumed
+  pub fn value(&self) -> Operand<'ctx> {
+    Operand::from_llvm(unsafe { LLVMGetOperand(self.0, 0) })
+  }
+}
+
+impl<'ctx> ValueOpcode for ResumeInstruction<'ctx> {
+  fn opcode(&self) -> Opcode {
+    Opcode::Resume
+  }
+}
+
+impl<'ctx> AsInstruction<'ctx> for ResumeInstruction<'ctx> {
+  fn as_instruction(&self) -> Instruction<'ctx> {
+    Instruction::Resume(*self)
+  }
+}
+
+impl_positional_value_ref!(ResumeInstruction, 0);
+
+impl_positional_from_llvm_value!(ResumeInstruction);
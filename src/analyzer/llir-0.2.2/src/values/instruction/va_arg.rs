@@ -0,0 +1,49 @@
+use llvm_sys::core::LLVMGetOperand;
+use llvm_sys::prelude::LLVMValueRef;
+use std::marker::PhantomData;
+
+use crate::values::*;
+use crate::*;
+
+/// [Variable argument instruction](https://llvm.org/docs/LangRef.html#va-arg-instruction)
+///
+/// The result is the next argument in the `va_list`, whose type is the type of the instruction
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct VAArgInstruction<'ctx>(LLVMValueRef, PhantomData<&'ctx ()>);
+
+impl_instr_debug!(VAArgInstruction);
+
+impl_as_operand_for_instr!(VAArgInstruction);
+
+impl_send_sync!(VAArgInstruction);
+
+impl<'ctx> GetType<'ctx> for VAArgInstruction<'ctx> {}
+
+impl<'ctx> GetDebugMetadata<'ctx> for VAArgInstruction<'ctx> {}
+
+impl<'ctx> InstructionDebugLoc for VAArgInstruction<'ctx> {}
+
+impl<'ctx> InstructionTrait<'ctx> for VAArgInstruction<'ctx> {}
+
+impl<'ctx> VAArgInstruction<'ctx> {
+  /// Get the pointer to the `va_list`
+  pub fn va_list(&self) -> Operand<'ctx> {
+    Operand::from_llvm(unsafe { LLVMGetOperand(self.0, 0) })
+  }
+}
+
+impl<'ctx> ValueOpcode for VAArgInstruction<'ctx> {
+  fn opcode(&self) -> Opcode {
+    Opcode::VAArg
+  }
+}
+
+impl<'ctx> AsInstruction<'ctx> for VAArgInstruction<'ctx> {
+  fn as_instruction(&self) -> Instruction<'ctx> {
+    Instruction::VAArg(*self)
+  }
+}
+
+impl_positional_value_ref!(VAArgInstruction, 0);
+
+impl_positional_from_llvm_value!(VAArgInstruction);
@@ -1,6 +1,7 @@
 use llvm_sys::prelude::LLVMValueRef;
 use std::marker::PhantomData;
 
+use crate::utils::md_node::*;
 use crate::values::*;
 use crate::*;
 
@@ -12,6 +13,13 @@ impl_as_operand_for_metadata!(ConstantAsMetadata);
 
 impl_send_sync!(ConstantAsMetadata);
 
+impl<'ctx> ConstantAsMetadata<'ctx> {
+  /// Get the constant wrapped in the metadata
+  pub fn value(&self) -> Option<Operand<'ctx>> {
+    md_node_operand(self.0, 0).map(Operand::from_llvm)
+  }
+}
+
 impl_positional_value_ref!(ConstantAsMetadata, 0);
 
 impl_positional_from_llvm_value!(ConstantAsMetadata);
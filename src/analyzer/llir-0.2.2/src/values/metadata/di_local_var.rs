@@ -1,6 +1,9 @@
+use llvm_sys::core::*;
+use llvm_sys::debuginfo::*;
 use llvm_sys::prelude::LLVMValueRef;
 use std::marker::PhantomData;
 
+use crate::utils::md_node::*;
 use crate::values::*;
 use crate::*;
 
@@ -12,6 +15,47 @@ impl_as_operand_for_metadata!(DILocalVariable);
 
 impl_send_sync!(DILocalVariable);
 
+impl<'ctx> DILocalVariable<'ctx> {
+  /// Get the source name of the variable
+  pub fn name(&self) -> Option<String> {
+    md_node_operand(self.0, 1).and_then(md_string)
+  }
+
+  /// Get the line number of the declaration
+  pub fn line(&self) -> usize {
+    unsafe { LLVMDIVariableGetLine(LLVMValueAsMetadata(self.0)) as usize }
+  }
+
+  /// Get the filename of the declaration
+  pub fn filename(&self) -> Option<String> {
+    di_file_name(unsafe { LLVMDIVariableGetFile(LLVMValueAsMetadata(self.0)) })
+  }
+
+  /// Get the declared type of the variable
+  pub fn ty(&self) -> Option<DIType<'ctx>> {
+    md_node_operand(self.0, 3).and_then(DIType::from_md_value)
+  }
+
+  /// Get the scope of the variable, either a lexical block or a DISubprogram
+  pub fn scope(&self) -> Option<Metadata<'ctx>> {
+    md_as_value(self.0, unsafe { LLVMDIVariableGetScope(LLVMValueAsMetadata(self.0)) }).map(Metadata::from_llvm)
+  }
+
+  /// Get the function where the variable is declared, going up through the lexical blocks
+  pub fn subprogram(&self) -> Option<DISubprogram<'ctx>> {
+    let mut scope = md_node_operand(self.0, 0)?;
+    loop {
+      match md_kind(scope) {
+        LLVMMetadataKind::LLVMDISubprogramMetadataKind => return Some(DISubprogram::from_llvm(scope)),
+        LLVMMetadataKind::LLVMDILexicalBlockMetadataKind | LLVMMetadataKind::LLVMDILexicalBlockFileMetadataKind => {
+          scope = md_node_operand(scope, 1)?;
+        }
+        _ => return None,
+      }
+    }
+  }
+}
+
 impl_positional_value_ref!(DILocalVariable, 0);
 
 impl_positional_from_llvm_value!(DILocalVariable);
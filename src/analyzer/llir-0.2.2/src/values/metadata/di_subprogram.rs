@@ -0,0 +1,91 @@
+use llvm_sys::core::*;
+use llvm_sys::debuginfo::*;
+use llvm_sys::prelude::LLVMValueRef;
+use std::marker::PhantomData;
+
+use crate::utils::md_node::*;
+use crate::values::*;
+use crate::*;
+
+/// DI Subprogram Metadata, the debug info of a function
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DISubprogram<'ctx>(LLVMValueRef, PhantomData<&'ctx ()>);
+
+impl_as_operand_for_metadata!(DISubprogram);
+
+impl_send_sync!(DISubprogram);
+
+impl<'ctx> DISubprogram<'ctx> {
+  /// Get the source name of the function
+  pub fn name(&self) -> Option<String> {
+    md_node_operand(self.0, 2).and_then(md_string)
+  }
+
+  /// Get the linkage name of the function, if it is different from the source name
+  pub fn linkage_name(&self) -> Option<String> {
+    md_node_operand(self.0, 3).and_then(md_string)
+  }
+
+  /// Get the line number of the definition
+  pub fn line(&self) -> usize {
+    unsafe { LLVMDISubprogramGetLine(LLVMValueAsMetadata(self.0)) as usize }
+  }
+
+  /// Get the filename of the definition
+  pub fn filename(&self) -> Option<String> {
+    di_file_name(unsafe { LLVMDIScopeGetFile(LLVMValueAsMetadata(self.0)) })
+  }
+
+  /// Get the subroutine type of the function
+  pub fn ty(&self) -> Option<DIType<'ctx>> {
+    md_node_operand(self.0, 4).and_then(DIType::from_md_value)
+  }
+
+  /// Get the return type; `None` for `void`
+  pub fn return_type(&self) -> Option<DIType<'ctx>> {
+    self.ty()?.subroutine_types().into_iter().next().flatten()
+  }
+
+  /// Get the declared types of the parameters
+  pub fn param_types(&self) -> Vec<DIType<'ctx>> {
+    match self.ty() {
+      Some(ty) => ty.subroutine_types().into_iter().skip(1).flatten().collect(),
+      None => vec![],
+    }
+  }
+
+  /// Check if the function takes variable arguments
+  pub fn is_var_arg(&self) -> bool {
+    match self.ty() {
+      Some(ty) => {
+        let types = ty.subroutine_types();
+        types.len() > 1 && types.last().map(Option::is_none).unwrap_or(false)
+      }
+      None => false,
+    }
+  }
+
+  /// Get the source signature, e.g. `int foo(struct_name *, size_t)`
+  pub fn signature(&self) -> String {
+    let ret = self
+      .return_type()
+      .map(|ty| ty.type_name())
+      .unwrap_or("void".to_string());
+    let mut params = self.param_types().iter().map(|ty| ty.type_name()).collect::<Vec<_>>();
+    if self.is_var_arg() {
+      params.push("...".to_string());
+    }
+    let name = self.name().unwrap_or_default();
+    format!("{} {}({})", ret, name, params.join(", "))
+  }
+}
+
+impl_positional_value_ref!(DISubprogram, 0);
+
+impl_positional_from_llvm_value!(DISubprogram);
+
+impl<'ctx> AsMetadata<'ctx> for DISubprogram<'ctx> {
+  fn as_metadata(&self) -> Metadata<'ctx> {
+    Metadata::DISubprogram(self.clone())
+  }
+}
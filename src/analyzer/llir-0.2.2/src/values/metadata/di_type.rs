@@ -0,0 +1,242 @@
+use llvm_sys::core::*;
+use llvm_sys::debuginfo::*;
+use llvm_sys::prelude::LLVMValueRef;
+use std::marker::PhantomData;
+
+use crate::utils::md_node::*;
+use crate::values::*;
+use crate::*;
+
+/// The kind of a debug info type
+///
+/// The C API does not expose the DWARF tag, so derived and composite types are
+/// classified by their shape: a derived type in the scope of a composite type is
+/// a member, a named one is a typedef, a sized one is a pointer, and the rest are
+/// qualifiers (`const`, `volatile`, `restrict`). Unions are reported as structs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DITypeKind {
+  Basic,
+  Pointer,
+  Typedef,
+  Qualifier,
+  Member,
+  Struct,
+  Array,
+  Enum,
+  Subroutine,
+}
+
+/// DI Type Metadata, i.e. DIBasicType, DIDerivedType, DICompositeType and DISubroutineType
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DIType<'ctx>(LLVMValueRef, PhantomData<&'ctx ()>);
+
+impl_as_operand_for_metadata!(DIType);
+
+impl_send_sync!(DIType);
+
+impl<'ctx> DIType<'ctx> {
+  /// Get the kind of the type
+  pub fn kind(&self) -> DITypeKind {
+    use LLVMMetadataKind::*;
+    match md_kind(self.0) {
+      LLVMDIBasicTypeMetadataKind => DITypeKind::Basic,
+      LLVMDISubroutineTypeMetadataKind => DITypeKind::Subroutine,
+      LLVMDICompositeTypeMetadataKind => {
+        let elements = self.elements();
+        if elements
+          .iter()
+          .any(|e| matches!(md_kind(*e), LLVMDISubrangeMetadataKind))
+        {
+          DITypeKind::Array
+        } else if self.base_type().is_some()
+          || elements
+            .iter()
+            .any(|e| matches!(md_kind(*e), LLVMDIEnumeratorMetadataKind))
+        {
+          DITypeKind::Enum
+        } else {
+          DITypeKind::Struct
+        }
+      }
+      _ => {
+        let in_composite = md_node_operand(self.0, 1)
+          .map(|scope| matches!(md_kind(scope), LLVMDICompositeTypeMetadataKind))
+          .unwrap_or(false);
+        if in_composite {
+          DITypeKind::Member
+        } else if self.name().is_some() {
+          DITypeKind::Typedef
+        } else if self.size_in_bits() != 0 {
+          DITypeKind::Pointer
+        } else {
+          DITypeKind::Qualifier
+        }
+      }
+    }
+  }
+
+  /// Get the name of the type; `None` for anonymous types, pointers and qualifiers
+  pub fn name(&self) -> Option<String> {
+    let mut len = 0;
+    let ptr = unsafe { LLVMDITypeGetName(LLVMValueAsMetadata(self.0), &mut len) };
+    if ptr.is_null() || len == 0 {
+      None
+    } else {
+      let bytes = unsafe { std::slice::from_raw_parts(ptr as *const u8, len) };
+      Some(String::from_utf8_lossy(bytes).into_owned())
+    }
+  }
+
+  /// Get the size in bits
+  pub fn size_in_bits(&self) -> u64 {
+    unsafe { LLVMDITypeGetSizeInBits(LLVMValueAsMetadata(self.0)) }
+  }
+
+  /// Get the offset in bits; only meaningful for members
+  pub fn offset_in_bits(&self) -> u64 {
+    unsafe { LLVMDITypeGetOffsetInBits(LLVMValueAsMetadata(self.0)) }
+  }
+
+  /// Get the line number of the declaration
+  pub fn line(&self) -> usize {
+    unsafe { LLVMDITypeGetLine(LLVMValueAsMetadata(self.0)) as usize }
+  }
+
+  /// Get the base type: the pointee of pointers, the aliased type of typedefs and
+  /// qualifiers, the type of members, the element type of arrays and the underlying
+  /// type of enums. `None` for `void`
+  pub fn base_type(&self) -> Option<DIType<'ctx>> {
+    match md_kind(self.0) {
+      LLVMMetadataKind::LLVMDIDerivedTypeMetadataKind | LLVMMetadataKind::LLVMDICompositeTypeMetadataKind => {
+        md_node_operand(self.0, 3).and_then(DIType::from_md_value)
+      }
+      _ => None,
+    }
+  }
+
+  /// Get the members of a struct, in the order of declaration
+  pub fn members(&self) -> Vec<DIType<'ctx>> {
+    if self.kind() != DITypeKind::Struct {
+      return vec![];
+    }
+    self
+      .elements()
+      .into_iter()
+      .filter_map(DIType::from_md_value)
+      .filter(|member| member.kind() == DITypeKind::Member)
+      .collect()
+  }
+
+  /// Get the member of a struct by its name
+  pub fn member(&self, name: &str) -> Option<DIType<'ctx>> {
+    self
+      .members()
+      .into_iter()
+      .find(|member| member.name().as_deref() == Some(name))
+  }
+
+  /// Get the number of elements of an array's first dimension, if known
+  pub fn num_elements(&self) -> Option<u64> {
+    if self.kind() != DITypeKind::Array {
+      return None;
+    }
+    let subrange = self.elements().into_iter().next()?;
+    let count = md_node_operand(subrange, 0)?;
+    if unsafe { LLVMIsAConstantInt(count) }.is_null() {
+      None
+    } else {
+      let count = unsafe { LLVMConstIntGetSExtValue(count) };
+      if count < 0 {
+        None
+      } else {
+        Some(count as u64)
+      }
+    }
+  }
+
+  /// Get the return type and the parameter types of a subroutine type. The return
+  /// type is `None` for `void`; a trailing `None` parameter marks variable arguments
+  pub fn subroutine_types(&self) -> Vec<Option<DIType<'ctx>>> {
+    if !matches!(md_kind(self.0), LLVMMetadataKind::LLVMDISubroutineTypeMetadataKind) {
+      return vec![];
+    }
+    match md_node_operand(self.0, 3) {
+      Some(types) => md_node_operands(types)
+        .into_iter()
+        .map(|ty| if ty.is_null() { None } else { DIType::from_md_value(ty) })
+        .collect(),
+      None => vec![],
+    }
+  }
+
+  /// Skip the typedefs and the qualifiers to get the underlying type; `None` if it is `void`
+  pub fn strip_typedefs(&self) -> Option<DIType<'ctx>> {
+    match self.kind() {
+      DITypeKind::Typedef | DITypeKind::Qualifier => self.base_type()?.strip_typedefs(),
+      _ => Some(*self),
+    }
+  }
+
+  /// Get the type in C syntax, e.g. `struct_name *`, `int[16]` or `size_t`.
+  /// Qualifiers are omitted since they cannot be told apart
+  pub fn type_name(&self) -> String {
+    let base_name = || self.base_type().map(|ty| ty.type_name()).unwrap_or("void".to_string());
+    match self.kind() {
+      DITypeKind::Basic | DITypeKind::Typedef | DITypeKind::Struct | DITypeKind::Enum => {
+        self.name().unwrap_or("<anonymous>".to_string())
+      }
+      DITypeKind::Pointer => format!("{} *", base_name()),
+      DITypeKind::Qualifier | DITypeKind::Member => base_name(),
+      DITypeKind::Array => match self.num_elements() {
+        Some(num) => format!("{}[{}]", base_name(), num),
+        None => format!("{}[]", base_name()),
+      },
+      DITypeKind::Subroutine => {
+        let mut types = self.subroutine_types().into_iter();
+        let ret = types
+          .next()
+          .flatten()
+          .map(|ty| ty.type_name())
+          .unwrap_or("void".to_string());
+        let params = types
+          .map(|ty| ty.map(|ty| ty.type_name()).unwrap_or("...".to_string()))
+          .collect::<Vec<_>>();
+        format!("{} ({})", ret, params.join(", "))
+      }
+    }
+  }
+
+  pub(crate) fn from_md_value(ptr: LLVMValueRef) -> Option<Self> {
+    use LLVMMetadataKind::*;
+    if unsafe { LLVMIsAMDNode(ptr) }.is_null() {
+      return None;
+    }
+    match md_kind(ptr) {
+      LLVMDIBasicTypeMetadataKind
+      | LLVMDIDerivedTypeMetadataKind
+      | LLVMDICompositeTypeMetadataKind
+      | LLVMDISubroutineTypeMetadataKind => Some(Self::from_llvm(ptr)),
+      _ => None,
+    }
+  }
+
+  fn elements(&self) -> Vec<LLVMValueRef> {
+    match md_node_operand(self.0, 4) {
+      Some(elements) => md_node_operands(elements)
+        .into_iter()
+        .filter(|element| !element.is_null())
+        .collect(),
+      None => vec![],
+    }
+  }
+}
+
+impl_positional_value_ref!(DIType, 0);
+
+impl_positional_from_llvm_value!(DIType);
+
+impl<'ctx> AsMetadata<'ctx> for DIType<'ctx> {
+  fn as_metadata(&self) -> Metadata<'ctx> {
+    Metadata::DIType(self.clone())
+  }
+}
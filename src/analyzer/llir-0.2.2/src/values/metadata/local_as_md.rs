@@ -1,6 +1,7 @@
 use llvm_sys::prelude::LLVMValueRef;
 use std::marker::PhantomData;
 
+use crate::utils::md_node::*;
 use crate::values::*;
 use crate::*;
 
@@ -12,6 +13,13 @@ impl_as_operand_for_metadata!(LocalAsMetadata);
 
 impl_send_sync!(LocalAsMetadata);
 
+impl<'ctx> LocalAsMetadata<'ctx> {
+  /// Get the local value wrapped in the metadata, i.e. an instruction or an argument
+  pub fn value(&self) -> Option<Operand<'ctx>> {
+    md_node_operand(self.0, 0).map(Operand::from_llvm)
+  }
+}
+
 impl_positional_value_ref!(LocalAsMetadata, 0);
 
 impl_positional_from_llvm_value!(LocalAsMetadata);
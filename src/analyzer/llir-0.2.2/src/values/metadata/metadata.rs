@@ -13,6 +13,8 @@ pub enum Metadata<'ctx> {
   DILabel(DILabel<'ctx>),
   DILocalVariable(DILocalVariable<'ctx>),
   DILocation(DILocation<'ctx>),
+  DISubprogram(DISubprogram<'ctx>),
+  DIType(DIType<'ctx>),
   DistinctMDOperandPlaceholder(DistinctMDOperandPlaceholder<'ctx>),
   GenericDINode(GenericDINode<'ctx>),
   LocalAsMetadata(LocalAsMetadata<'ctx>),
@@ -29,6 +31,11 @@ impl<'ctx> FromLLVMValue for Metadata<'ctx> {
       LLVMDILabelMetadataKind => Self::DILabel(DILabel::from_llvm(ptr)),
       LLVMDILocalVariableMetadataKind => Self::DILocalVariable(DILocalVariable::from_llvm(ptr)),
       LLVMDILocationMetadataKind => Self::DILocation(DILocation::from_llvm(ptr)),
+      LLVMDISubprogramMetadataKind => Self::DISubprogram(DISubprogram::from_llvm(ptr)),
+      LLVMDIBasicTypeMetadataKind
+      | LLVMDIDerivedTypeMetadataKind
+      | LLVMDICompositeTypeMetadataKind
+      | LLVMDISubroutineTypeMetadataKind => Self::DIType(DIType::from_llvm(ptr)),
       LLVMDistinctMDOperandPlaceholderMetadataKind => {
         Self::DistinctMDOperandPlaceholder(DistinctMDOperandPlaceholder::from_llvm(ptr))
       }
@@ -48,6 +55,8 @@ impl<'ctx> ValueRef for Metadata<'ctx> {
       Self::DILabel(l) => l.value_ref(),
       Self::DILocalVariable(lv) => lv.value_ref(),
       Self::DILocation(l) => l.value_ref(),
+      Self::DISubprogram(s) => s.value_ref(),
+      Self::DIType(t) => t.value_ref(),
       Self::DistinctMDOperandPlaceholder(d) => d.value_ref(),
       Self::GenericDINode(g) => g.value_ref(),
       Self::LocalAsMetadata(lam) => lam.value_ref(),
@@ -12,6 +12,8 @@ mod local_as_md;
 mod di_expression;
 mod di_local_var;
 mod di_location;
+mod di_subprogram;
+mod di_type;
 mod md_tuple;
 pub use const_as_md::*;
 pub use metadata::*;
@@ -23,4 +25,6 @@ pub use local_as_md::*;
 pub use di_local_var::*;
 pub use di_expression::*;
 pub use di_location::*;
+pub use di_subprogram::*;
+pub use di_type::*;
 pub use md_tuple::*;
@@ -8,6 +8,9 @@
 //! - [Operand](enum.Operand.html)
 //!   - [Instruction](enum.Instruction.html)
 //!     - [Alloca](struct.AllocaInstruction.html)
+//!     - [AtomicCmpXchg](struct.AtomicCmpXchgInstruction.html)
+//!     - [AtomicRMW](struct.AtomicRMWInstruction.html)
+//!       - [AtomicRMW Binary Opcode](enum.AtomicRMWBinaryOpcode.html) Opcode enum for atomicrmw instructions
 //!     - [Binary](struct.BinaryInstruction.html)
 //!       - [Binary Opcode](enum.BinaryOpcode.html) Opcode enum for binary instructions
 //!     - [Branch](enum.BranchInstruction.html)
@@ -15,16 +18,22 @@
 //!       - [Unconditional Branch](struct.UnconditionalBranchInstruction.html)
 //!     - [Call](struct.CallInstruction.html)
 //!     - [CallBr](struct.CallBrInstruction.html)
+//!     - [CleanupPad](struct.CleanupPadInstruction.html)
 //!     - [ExtractValue](struct.ExtractValueInstruction.html)
 //!     - [FCmp](struct.FCmpInstruction.html)
 //!       - [FCmp Predicate](enum.FCmpPredicate.html) Floating point comparison predicate for fcmp instructions
+//!     - [Fence](struct.FenceInstruction.html)
+//!     - [Freeze](struct.FreezeInstruction.html)
 //!     - [Get Element Pointer](struct.GetElementPtr.html)
 //!     - [ICmp](struct.ICmpInstruction.html)
 //!       - [ICmp Predicate](enum.ICmpPredicate.html) Integer comparison predicate for icmp instructions
 //!     - [IndirectBranch](struct.IndirectBranchInstruction.html)
 //!     - [InsertValue](struct.InsertValueInstruction.html)
+//!     - [Invoke](struct.InvokeInstruction.html)
+//!     - [LandingPad](struct.LandingPadInstruction.html)
 //!     - [Load](struct.LoadInstruction.html)
 //!     - [PHI](struct.PhiInstruction.html)
+//!     - [Resume](struct.ResumeInstruction.html)
 //!     - [Return](struct.ReturnInstruction.html)
 //!     - [Select](struct.SelectInstruction.html)
 //!     - [Store](struct.StoreInstruction.html)
@@ -32,6 +41,7 @@
 //!     - [Unary](struct.UnaryInstruction.html)
 //!       - [Unary Opcode](enum.UnaryOpcode.html) Opcode enum for unary instructions
 //!     - [Unreachable](struct.UnreachableInstruction.html)
+//!     - [VAArg](struct.VAArgInstruction.html)
 //!   - [Constant](enum.Constant.html)
 //!     - [Function](struct.Function.html)
 //!     - [Global](enum.Global.html)
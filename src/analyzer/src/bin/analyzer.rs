@@ -3,12 +3,16 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use structopt::StructOpt;
 
-use analyzer::{call_graph::*, feature_extraction::*, options::*, slicer::*, symbolic_execution::*, utils::*};
+use analyzer::{
+    call_graph::*, feature_extraction::*, indirect_call::*, options::*, slicer::*, storage::*, symbolic_execution::*,
+    utils::*,
+};
 
 #[derive(StructOpt, Debug, Clone)]
 #[structopt(name = "analyzer")]
 pub struct Options {
     //************************** BasicOption & GeneralOptions & IOOption **************************//
+    // The input bitcode or textual IR (`.ll`) file, or a directory of such files
     #[structopt(index = 1, required = true, value_name = "INPUT")]
     pub input: String,
 
@@ -18,6 +22,15 @@ pub struct Options {
     #[structopt(long, takes_value = true, value_name = "SUBFOLDER")]
     pub subfolder: Option<String>,
 
+    // Store slices, traces, features and metadata in the SQLite database instead of json files,
+    // relative to the output directory
+    #[structopt(long, takes_value = true, value_name = "DATABASE")]
+    pub database: Option<String>,
+
+    // Other bitcode files (or directories of them) linked with the input for whole-program analysis
+    #[structopt(long, takes_value = true, value_name = "LINK_INPUT")]
+    pub link_inputs: Option<Vec<String>>,
+
     // Print call graph
     #[structopt(long)]
     pub print_call_graph: bool,
@@ -25,6 +38,10 @@ pub struct Options {
     #[structopt(long)]
     pub print_options: bool,
 
+    // Skip the slices completed by a previous run with the same inputs and options
+    #[structopt(long)]
+    pub resume: bool,
+
     // Serialize execution rather than parallel
     #[structopt(short = "s", long)]
     pub use_serial: bool,
@@ -50,6 +67,10 @@ pub struct Options {
 
     #[structopt(long)]
     pub feature_only: bool,
+
+    // The functions checking error pointers, e.g., `IS_ERR`
+    #[structopt(long, takes_value = true, value_name = "CHECKER_FUNCTION")]
+    pub checker_functions: Option<Vec<String>>,
     //************************** BasicOption & GeneralOptions & IOOption **************************//
 
     //***************************************** SliceOptions *************************************//
@@ -62,6 +83,10 @@ pub struct Options {
     )]
     pub slice_depth: usize,
 
+    // How to resolve indirect calls in the call graph: none, type (signature) or precise
+    #[structopt(long, takes_value = true, default_value = "none", value_name = "MODE")]
+    pub indirect_calls: IndirectCallResolution,
+
     // Maximum number of blocks per slice
     #[structopt(long, takes_value = true, default_value = "1000", value_name = "MAX_AVG_NUM_BLOCKS")]
     pub max_num_blocks: usize,
@@ -108,6 +133,18 @@ pub struct Options {
     // Random schedule the execution work
     #[structopt(long)]
     pub not_random_scheduling: bool,
+
+    // Fork the path on a symbolic select condition rather than keeping an ite value
+    #[structopt(long)]
+    pub split_select: bool,
+
+    // Encode path constraints with unbounded integers rather than bit-vectors
+    #[structopt(long)]
+    pub use_int_encoding: bool,
+
+    // The seed for random scheduling, derived per slice. Seeded by the current time if not given
+    #[structopt(long, takes_value = true, value_name = "SEED")]
+    pub seed: Option<u64>,
     //*********************************** SymbolicExecutionOptions *******************************//
 }
 
@@ -136,9 +173,41 @@ impl IOOptions for Options {
             None => None,
         }
     }
+
+    fn database(&self) -> Option<&str> {
+        self.database.as_deref()
+    }
 }
 
 impl Options {
+    fn input_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![self.input_path()];
+        if let Some(link_inputs) = &self.link_inputs {
+            paths.extend(link_inputs.iter().map(PathBuf::from));
+        }
+        paths
+    }
+
+    // The options affecting the generated slices and traces, checked when resuming
+    fn fingerprint(&self) -> String {
+        format!(
+            "{:?} {:?}",
+            (self.slice_depth, self.indirect_calls, self.max_num_blocks),
+            (
+                self.max_timeout,
+                self.max_node_per_trace,
+                self.max_explored_trace_per_slice,
+                self.max_trace_per_slice,
+                self.step_in_anytime,
+                self.rough_mode,
+                self.not_random_scheduling,
+                self.split_select,
+                self.use_int_encoding,
+                self.seed,
+            )
+        )
+    }
+
     fn metadata_file_path(&self) -> Option<PathBuf> {
         if let Some(filename) = &self.metadata_file {
             Some(self.output_path().join(filename))
@@ -154,12 +223,11 @@ impl Options {
             None
         }
     }
+}
 
-    fn num_slices(&self, target: &str) -> usize {
-        match std::fs::read_dir(self.slice_target_dir(target)) {
-            Ok(dirs) => dirs.count(),
-            _ => 0,
-        }
+impl CallGraphOptions for Options {
+    fn indirect_call_resolution(&self) -> IndirectCallResolution {
+        self.indirect_calls
     }
 }
 
@@ -217,9 +285,28 @@ impl SymbolicExecutionOptions for Options {
     fn not_random_scheduling(&self) -> bool {
         self.not_random_scheduling
     }
+
+    fn split_select(&self) -> bool {
+        self.split_select
+    }
+
+    fn use_int_encoding(&self) -> bool {
+        self.use_int_encoding
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.seed
+    }
 }
 
-impl FeatureExtractorOptions for Options {}
+impl FeatureExtractorOptions for Options {
+    fn checker_functions(&self) -> Vec<String> {
+        match &self.checker_functions {
+            Some(checker_functions) => checker_functions.clone(),
+            None => DEFAULT_CHECKER_FUNCTIONS.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+}
 
 fn main() -> Result<(), String> {
     let options = Options::from_args();
@@ -230,16 +317,29 @@ fn main() -> Result<(), String> {
     // Load a logging context
     let mut logging_ctx = LoggingContext::new(&options)?;
 
+    // Open the storage of slices, traces and features
+    let storage = open_storage(&options)?;
+    let storage = storage.as_ref();
+
     // Load the byte code module and generate analyzer context
     logging_ctx.log_loading_bc()?;
     let llctx = llir::Context::create();
-    let llmod = llctx
-        .load_module(&options.input_path())
-        .map_err(|err| err.to_string())?;
+    let bc_files = bitcode_files(&options.input_paths())?;
+    let llmod = load_linked_module(&llctx, &bc_files)?;
+
+    // The key of completion records, so that a changed input or changed options invalidate them
+    let resume_key = if options.resume {
+        Some(format!(
+            "{:016x}",
+            stable_hash_files(&bc_files, &options.fingerprint())?
+        ))
+    } else {
+        None
+    };
 
     // Generate call graph
     logging_ctx.log_generating_call_graph()?;
-    let call_graph = CallGraph::from_module(&llmod);
+    let call_graph = CallGraph::from_module(&llmod, &options);
     if options.print_call_graph {
         call_graph.print();
     }
@@ -252,17 +352,27 @@ fn main() -> Result<(), String> {
     let occurrences = if !options.feature_only {
         // Generate slices from the edges
         logging_ctx.log_generated_call_edges(target_edges_map.num_elements())?;
-        let target_slices_map = TargetSlicesMap::from_target_edges_map(&target_edges_map, &call_graph, &options);
+        let (target_slices_map, skipped_slices) =
+            TargetSlicesMap::from_target_edges_map(&target_edges_map, &call_graph, &options);
         let occurrences = keyed_num_elements(&target_slices_map, &llmod);
         // Dump slices to file
         logging_ctx.log_generated_slices(target_slices_map.num_elements())?;
-        target_slices_map.dump(&options);
+        let num_trimmed = skipped_slices.iter().filter(|s| s.trimmed).count();
+        logging_ctx.log_skipped_slices(skipped_slices.len() - num_trimmed, num_trimmed)?;
+        target_slices_map.dump(&options, storage);
 
         // Divide target slices into batches
         let num_batches = 1 + target_slices_map.num_elements() / options.batch_size;
         let batchmap = target_slices_map.batches(options.use_batch, options.batch_size);
         logging_ctx.log_dividing_batches(options.use_batch, num_batches)?;
         let mut global_metadata = MetaData::new();
+        for skipped_slice in &skipped_slices {
+            if skipped_slice.trimmed {
+                global_metadata.add_trimmed_slice(skipped_slice.to_json());
+            } else {
+                global_metadata.add_skipped_slice(skipped_slice.to_json());
+            }
+        }
         for (i, target_slices_map) in batchmap {
             logging_ctx.log_executing_batch(
                 i,
@@ -272,7 +382,7 @@ fn main() -> Result<(), String> {
                 target_slices_map.num_elements(),
             )?;
             // Symbolic execution
-            let sym_exec_ctx = SymbolicExecutionContext::new(&options);
+            let sym_exec_ctx = SymbolicExecutionContext::new(&options, storage, resume_key.clone());
             let metadata = sym_exec_ctx.execute_target_slices_map(target_slices_map);
             global_metadata = global_metadata.combine(metadata.clone());
             logging_ctx.log_finished_execution_batch(i, options.use_batch, metadata)?;
@@ -282,11 +392,12 @@ fn main() -> Result<(), String> {
         if let Some(filename) = options.metadata_file_path() {
             global_metadata.dump(filename)?;
         }
+        storage.dump_metadata(options.basename_of_bc_file(), &global_metadata.to_json())?;
 
         occurrences
     } else {
         // If not, we directly load slices information from file
-        load_target_num_slices_map(target_edges_map, &llmod, &options)
+        load_target_num_slices_map(target_edges_map, &llmod, &options, storage)
     };
 
     // Dump the occurrences(format: {"func_name": (has_return_type, slices_num), ...}) to file
@@ -298,12 +409,12 @@ fn main() -> Result<(), String> {
     if !options.no_feature {
         // Directly extract features
         logging_ctx.log_extracting_features()?;
-        let feature_extract_ctx = FeatureExtractionContext::new(&llmod, occurrences, &options)?;
+        let feature_extract_ctx = FeatureExtractionContext::new(&llmod, occurrences, &options, storage)?;
         feature_extract_ctx.extract_features(&mut logging_ctx);
         logging_ctx.log_finished_extracting_features()?;
     }
 
-    Ok(())
+    storage.flush()
 }
 
 // (func_name, (has_return_type, slices_num))
@@ -324,12 +435,13 @@ fn load_target_num_slices_map(
     target_edges_map: TargetEdgesMap,
     module: &Module,
     options: &Options,
+    storage: &dyn Storage,
 ) -> HashMap<String, (bool, usize)> {
     let func_types = module.function_types();
     target_edges_map
         .into_iter()
         .map(|(target, _)| {
-            let num_slices = options.num_slices(&target);
+            let num_slices = storage.num_slices(&target, options.basename_of_bc_file());
             (target.clone(), (func_types[&target].has_return_type(), num_slices))
         })
         .collect()
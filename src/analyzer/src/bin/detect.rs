@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+use analyzer::{detect::*, options::*, spec_inference::*, storage::*, utils::*};
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "detect", about = "detect API misuses against the specifications")]
+pub struct Options {
+    // The output directory of the analyzer, containing `features/`. The reports are written here
+    #[structopt(index = 1, required = true, value_name = "INPUT")]
+    input: String,
+
+    // The spec file by `spec-infer`. Infer the specifications from the features if not given
+    #[structopt(long, takes_value = true, value_name = "SPEC")]
+    spec: Option<String>,
+
+    // Read features from the SQLite database, relative to the input directory
+    #[structopt(long, takes_value = true, value_name = "DATABASE")]
+    database: Option<String>,
+
+    #[structopt(long, takes_value = true, value_name = "TARGET_FUNCTION")]
+    target_fn: Option<String>,
+
+    // Only one of `retval`, `arg.pre`, `arg.post` and `causality`
+    #[structopt(long = "type", takes_value = true, value_name = "TYPE")]
+    check_type: Option<String>,
+
+    // The fixed threshold within (0, 1)
+    #[structopt(long, takes_value = true)]
+    threshold: Option<f64>,
+
+    // The hyper parameter of the sigmoid threshold, 500 by default
+    #[structopt(long, takes_value = true)]
+    rho: Option<f64>,
+
+    // Count the same features at a location only once
+    #[structopt(long)]
+    rm_dup: bool,
+
+    #[structopt(long)]
+    only_report_locations: bool,
+}
+
+impl IOOptions for Options {
+    fn input_path(&self) -> PathBuf {
+        PathBuf::from(&self.input)
+    }
+
+    fn output_path(&self) -> PathBuf {
+        PathBuf::from(&self.input)
+    }
+
+    fn basename_of_bc_file(&self) -> Option<&str> {
+        None
+    }
+
+    fn database(&self) -> Option<&str> {
+        self.database.as_deref()
+    }
+}
+
+impl SpecInferenceOptions for Options {
+    fn threshold_function(&self) -> ThresholdFunction {
+        ThresholdFunction::new(self.threshold, self.rho)
+    }
+
+    fn remove_dup(&self) -> bool {
+        self.rm_dup
+    }
+}
+
+fn main() -> Result<(), String> {
+    let options = Options::from_args();
+    let storage = open_storage(&options)?;
+    let storage = storage.as_ref();
+
+    let check_types = match &options.check_type {
+        Some(name) => vec![CheckType::from_name(name).ok_or(format!("Unknown check type {}", name))?],
+        None => CheckType::all(),
+    };
+
+    let mut specs: BTreeMap<String, Specification> = match &options.spec {
+        Some(path) => load_json_t(&PathBuf::from(path))?,
+        None => {
+            let targets = match &options.target_fn {
+                Some(target) => vec![target.clone()],
+                None => storage.feature_targets(),
+            };
+            infer_specifications(storage, &targets, &options)
+        }
+    };
+    if let Some(target) = &options.target_fn {
+        specs.retain(|t, _| t == target);
+    }
+
+    println!("Detecting for {} functions", specs.len());
+    let reports = detect(storage, &specs, &check_types, options.remove_dup());
+
+    let json = serde_json::to_string_pretty(&reports).map_err(|_| "Cannot turn reports into json".to_string())?;
+    let mut file = File::create(options.output_path().join("bugreport.json"))
+        .map_err(|_| "Cannot create bug report file".to_string())?;
+    file.write_all(json.as_bytes())
+        .map_err(|_| "Cannot write to bug report file".to_string())?;
+
+    let text = format_bug_reports(&reports, options.only_report_locations);
+    let mut file = File::create(options.output_path().join("bugreport.txt"))
+        .map_err(|_| "Cannot create bug report file".to_string())?;
+    file.write_all(text.as_bytes())
+        .map_err(|_| "Cannot write to bug report file".to_string())?;
+
+    println!("Have dumped {} reports to {:?}", reports.len(), options.output_path());
+    Ok(())
+}
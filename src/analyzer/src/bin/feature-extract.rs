@@ -3,11 +3,10 @@ use llir::types::*;
 use rayon::prelude::*;
 use serde::Deserialize;
 use std::collections::HashMap;
-use std::fs;
 use std::path::PathBuf;
 use structopt::StructOpt;
 
-use analyzer::{feature_extraction::*, options::*, utils::*};
+use analyzer::{feature_extraction::*, options::*, storage::*, utils::*};
 
 #[derive(StructOpt, Debug)]
 #[structopt(name = "feature-extract")]
@@ -17,6 +16,14 @@ pub struct Options {
 
     #[structopt(index = 2, required = true, value_name = "OUTPUT")]
     output: String,
+
+    // Read slices & traces from (and store features into) the SQLite database, relative to the output directory
+    #[structopt(long, takes_value = true, value_name = "DATABASE")]
+    database: Option<String>,
+
+    // The functions checking error pointers, e.g., `IS_ERR`
+    #[structopt(long, takes_value = true, value_name = "CHECKER_FUNCTION")]
+    checker_functions: Option<Vec<String>>,
 }
 
 impl IOOptions for Options {
@@ -31,6 +38,19 @@ impl IOOptions for Options {
     fn basename_of_bc_file(&self) -> Option<&str> {
         None
     }
+
+    fn database(&self) -> Option<&str> {
+        self.database.as_deref()
+    }
+}
+
+impl FeatureExtractorOptions for Options {
+    fn checker_functions(&self) -> Vec<String> {
+        match &self.checker_functions {
+            Some(checker_functions) => checker_functions.clone(),
+            None => DEFAULT_CHECKER_FUNCTIONS.iter().map(|f| f.to_string()).collect(),
+        }
+    }
 }
 
 // Input file
@@ -66,24 +86,12 @@ pub type TargetPackageNumSlicesMap = HashMap<String, (bool, Vec<(String, usize)>
 
 pub type Packages<'ctx> = HashMap<String, HashMap<String, FunctionType<'ctx>>>;
 
-fn load_slices(options: &Options, func: &str, package: &str, num_slices: usize) -> Vec<Slice> {
+fn load_slices(storage: &dyn Storage, func: &str, package: &str, num_slices: usize) -> Vec<Slice> {
     (0..num_slices)
         .collect::<Vec<_>>()
         .into_par_iter()
         .map(|slice_id| {
-            let path = options.slice_target_package_file_path(func, package, slice_id);
-            load_json_t(&path).expect("Cannot load slice file")
-        })
-        .collect::<Vec<_>>()
-}
-
-fn load_trace_file_paths(options: &Options, func: &str, package: &str, slice_id: usize) -> Vec<(usize, PathBuf)> {
-    fs::read_dir(options.trace_target_package_slice_dir(func, package, slice_id))
-        .expect("Cannot read traces folder")
-        .map(|path| {
-            let path = path.expect("Cannot read traces folder path").path();
-            let trace_id = path.file_stem().unwrap().to_str().unwrap().parse::<usize>().unwrap();
-            (trace_id, path)
+            FeatureExtractors::load_slice(storage, func, Some(package), slice_id).expect("Cannot load slice file")
         })
         .collect::<Vec<_>>()
 }
@@ -91,6 +99,8 @@ fn load_trace_file_paths(options: &Options, func: &str, package: &str, slice_id:
 fn main() -> Result<(), String> {
     let options = Options::from_args();
     let input = Input::from_options(&options);
+    let storage = open_storage(&options)?;
+    let storage = storage.as_ref();
 
     println!("Building target functions map...");
 
@@ -110,17 +120,13 @@ fn main() -> Result<(), String> {
 
     func_num_slices_map.into_par_iter().progress_with(func_map_pb).for_each(
         |(func, (has_return_type, package_num_slices))| {
-            let extractors = FeatureExtractors::extractors_for_target(has_return_type);
+            let extractors = FeatureExtractors::extractors_for_target(has_return_type, &options);
 
             package_num_slices.into_par_iter().for_each(|(package, num_slices)| {
-                let slices = load_slices(&options, &func, &package, num_slices);
+                let slices = load_slices(storage, &func, &package, num_slices);
                 slices.par_iter().enumerate().for_each(|(slice_id, slice)| {
-                    // First create directory
-                    fs::create_dir_all(options.feature_target_package_slice_dir(&func, &package, slice_id))
-                        .expect("Cannot create features func slice directory");
-
-                    // Then load trace file directories
-                    let traces = load_trace_file_paths(&options, &func, &package, slice_id);
+                    // Load the ids of traces
+                    let traces = storage.trace_ids(&func, Some(&package), slice_id);
 
                     // let style = ProgressStyle::default_bar()
                     // 	.template("[{elapsed_precise}] {bar:40.cyan/white} Traces:{pos:>5}/{len:5} {msg}")
@@ -132,16 +138,17 @@ fn main() -> Result<(), String> {
                     traces
                         .into_par_iter()
                         // .progress_with(pb)
-                        .for_each(|(trace_id, dir_entry)| {
-                            let trace = FeatureExtractors::load_trace(&dir_entry);
+                        .for_each(|trace_id| {
+                            let trace =
+                                FeatureExtractors::load_trace(storage, &func, Some(&package), slice_id, trace_id);
 
                             match trace {
                                 Ok(trace) => {
                                     // Extract and dump features
                                     let features = extractors.extract_features(slice_id, &slice, &trace);
-                                    let path = options
-                                        .feature_target_package_slice_file_path(&func, &package, slice_id, trace_id);
-                                    dump_json(&features, path).expect("Cannot dump features json");
+                                    storage
+                                        .dump_features(&func, Some(&package), slice_id, trace_id, &features.to_json())
+                                        .expect("Cannot dump features json");
                                 }
                                 _ => {}
                             }
@@ -151,5 +158,5 @@ fn main() -> Result<(), String> {
         },
     );
 
-    Ok(())
+    storage.flush()
 }
@@ -0,0 +1,9 @@
+use analyzer::feature_extraction::*;
+
+// Print the JSON Schema of the features dumped by `analyzer` and `feature-extract`
+fn main() -> Result<(), String> {
+    let schema =
+        serde_json::to_string_pretty(&TraceFeatures::schema()).map_err(|_| "Cannot dump schema".to_string())?;
+    println!("{}", schema);
+    Ok(())
+}
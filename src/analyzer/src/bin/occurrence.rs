@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use structopt::StructOpt;
 
-use analyzer::{call_graph::*, options::*, utils::*};
+use analyzer::{call_graph::*, indirect_call::*, options::*, utils::*};
 use llir::{types::*, values::*};
 
 #[derive(StructOpt, Debug, Clone)]
@@ -31,6 +31,13 @@ impl IOOptions for Options {
     }
 }
 
+// Occurrences only count the direct call sites
+impl CallGraphOptions for Options {
+    fn indirect_call_resolution(&self) -> IndirectCallResolution {
+        IndirectCallResolution::None
+    }
+}
+
 impl Options {
     pub fn input_bc_name(&self) -> String {
         format!("{}", self.input_path().file_name().unwrap().to_str().unwrap())
@@ -86,7 +93,7 @@ fn main() -> Result<(), String> {
 
     // Generate call graph
     logging_ctx.log_generating_call_graph()?;
-    let call_graph = CallGraph::from_module(&llmod);
+    let call_graph = CallGraph::from_module(&llmod, &options);
 
     // Generate occurrence map
     logging_ctx.log_generating_occurrence_map()?;
@@ -0,0 +1,83 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+use analyzer::{options::*, spec_inference::*, storage::*};
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "spec-infer", about = "infer specifications from the features")]
+pub struct Options {
+    // The output directory of the analyzer, containing `features/`
+    #[structopt(index = 1, required = true, value_name = "INPUT")]
+    input: String,
+
+    // The spec file, i.e., `{"target": {"causal": ..., "arg.pre": ..., ...}, ...}`
+    #[structopt(index = 2, required = true, value_name = "OUTPUT")]
+    output: String,
+
+    // Read features from the SQLite database, relative to the input directory
+    #[structopt(long, takes_value = true, value_name = "DATABASE")]
+    database: Option<String>,
+
+    #[structopt(long, takes_value = true, value_name = "TARGET_FUNCTION")]
+    target_fn: Option<String>,
+
+    // The fixed threshold within (0, 1)
+    #[structopt(long, takes_value = true)]
+    threshold: Option<f64>,
+
+    // The hyper parameter of the sigmoid threshold, 500 by default
+    #[structopt(long, takes_value = true)]
+    rho: Option<f64>,
+
+    // Count the same features at a location only once
+    #[structopt(long)]
+    rm_dup: bool,
+}
+
+impl IOOptions for Options {
+    fn input_path(&self) -> PathBuf {
+        PathBuf::from(&self.input)
+    }
+
+    fn output_path(&self) -> PathBuf {
+        PathBuf::from(&self.input)
+    }
+
+    fn basename_of_bc_file(&self) -> Option<&str> {
+        None
+    }
+
+    fn database(&self) -> Option<&str> {
+        self.database.as_deref()
+    }
+}
+
+impl SpecInferenceOptions for Options {
+    fn threshold_function(&self) -> ThresholdFunction {
+        ThresholdFunction::new(self.threshold, self.rho)
+    }
+
+    fn remove_dup(&self) -> bool {
+        self.rm_dup
+    }
+}
+
+fn main() -> Result<(), String> {
+    let options = Options::from_args();
+    let storage = open_storage(&options)?;
+    let storage = storage.as_ref();
+
+    let targets = match &options.target_fn {
+        Some(target) => vec![target.clone()],
+        None => storage.feature_targets(),
+    };
+    println!("Inferring specifications for {} functions...", targets.len());
+    let specs = infer_specifications(storage, &targets, &options);
+
+    let json = serde_json::to_string_pretty(&specs).map_err(|_| "Cannot turn specs into json".to_string())?;
+    let mut file = File::create(&options.output).map_err(|_| "Cannot create spec file".to_string())?;
+    file.write_all(json.as_bytes())
+        .map_err(|_| "Cannot write to spec file".to_string())
+}
@@ -3,6 +3,12 @@ use petgraph::graph::{DiGraph, EdgeIndex, Graph, NodeIndex};
 use petgraph::*;
 use std::collections::HashMap;
 
+use crate::indirect_call::*;
+
+pub trait CallGraphOptions {
+    fn indirect_call_resolution(&self) -> IndirectCallResolution;
+}
+
 pub trait FunctionUtil<'ctx> {
     fn simp_name(&self) -> String;
 }
@@ -26,10 +32,33 @@ impl<'ctx> FunctionUtil<'ctx> for Function<'ctx> {
     }
 }
 
+// How the callee of a call edge is determined. Only direct edges are certain; the type signature
+// ones over-approximate the callees of an indirect call
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CallEdgeKind {
+    Direct,
+    TypeSignature,
+    Precise,
+}
+
+impl CallEdgeKind {
+    pub fn is_indirect(&self) -> bool {
+        *self != CallEdgeKind::Direct
+    }
+
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            CallEdgeKind::Direct => "direct",
+            CallEdgeKind::TypeSignature => "type_signature",
+            CallEdgeKind::Precise => "precise",
+        }
+    }
+}
+
 pub struct CallEdge<'ctx> {
     pub caller: Function<'ctx>,
     pub callee: Function<'ctx>,
-    pub instr: CallInstruction<'ctx>,
+    pub instr: CallBaseInstruction<'ctx>,
 }
 
 // Output formatter of call graph edge
@@ -53,7 +82,7 @@ fn skip_intrinsics_funcs(func_name: String) -> bool {
 }
 
 // CallGraph is defined by function vertices + instruction edges connecting caller & callee
-pub type CallGraphRaw<'ctx> = DiGraph<Function<'ctx>, CallInstruction<'ctx>>;
+pub type CallGraphRaw<'ctx> = DiGraph<Function<'ctx>, CallBaseInstruction<'ctx>>;
 
 pub trait CallGraphTrait<'ctx> {
     type Edge;
@@ -160,16 +189,20 @@ impl IndexedGraphPath {
     }
 }
 
-pub type CallGraphPath<'ctx> = GraphPath<Function<'ctx>, CallInstruction<'ctx>>;
+pub type CallGraphPath<'ctx> = GraphPath<Function<'ctx>, CallBaseInstruction<'ctx>>;
 
 pub struct CallGraph<'ctx> {
     pub graph: CallGraphRaw<'ctx>,
     pub function_id_map: FunctionIdMap<'ctx>,
+    // The kinds of indirect edges; edges not in the map are direct
+    pub edge_kinds: HashMap<EdgeIndex, CallEdgeKind>,
 }
 
 impl<'ctx> CallGraph<'ctx> {
-    pub fn from_module(module: &Module<'ctx>) -> Self {
+    pub fn from_module(module: &Module<'ctx>, options: &impl CallGraphOptions) -> Self {
         let mut value_id_map: HashMap<Function<'ctx>, NodeIndex> = HashMap::new();
+        let mut edge_kinds = HashMap::new();
+        let resolver = IndirectCallResolver::new(module, options.indirect_call_resolution());
 
         // Generate Call Graph by iterating through all blocks & instructions for each function
         let mut cg = Graph::new();
@@ -184,8 +217,9 @@ impl<'ctx> CallGraph<'ctx> {
                 .clone();
             for b in caller.iter_blocks() {
                 for i in b.iter_instructions() {
-                    match i {
-                        Instruction::Call(call_instr) => {
+                    // Both calls and invokes are call sites
+                    match CallBaseInstruction::from_instruction(i) {
+                        Some(call_instr) => {
                             if !call_instr.is_intrinsic_call() {
                                 match call_instr.callee_function() {
                                     Some(callee) => {
@@ -195,11 +229,23 @@ impl<'ctx> CallGraph<'ctx> {
                                             .clone();
                                         cg.add_edge(caller_id, callee_id, call_instr);
                                     }
-                                    None => {}
+                                    None => {
+                                        for (callee, kind) in resolver.resolve(&call_instr) {
+                                            if !skip_intrinsics_funcs(callee.name()) {
+                                                continue;
+                                            }
+                                            let callee_id = value_id_map
+                                                .entry(callee)
+                                                .or_insert_with(|| cg.add_node(callee))
+                                                .clone();
+                                            let edge_id = cg.add_edge(caller_id, callee_id, call_instr);
+                                            edge_kinds.insert(edge_id, kind);
+                                        }
+                                    }
                                 }
                             }
                         }
-                        _ => {}
+                        None => {}
                     }
                 }
             }
@@ -209,6 +255,14 @@ impl<'ctx> CallGraph<'ctx> {
         Self {
             graph: cg,
             function_id_map: value_id_map,
+            edge_kinds,
+        }
+    }
+
+    pub fn edge_kind(&self, edge_id: EdgeIndex) -> CallEdgeKind {
+        match self.edge_kinds.get(&edge_id) {
+            Some(kind) => *kind,
+            None => CallEdgeKind::Direct,
         }
     }
 
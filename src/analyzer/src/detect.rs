@@ -0,0 +1,498 @@
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::feature_extraction::*;
+use crate::feature_extractors::*;
+use crate::spec_inference::*;
+use crate::storage::*;
+use crate::utils::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckType {
+    ReturnValue,
+    ArgumentPrecondition,
+    ArgumentPostcondition,
+    Causality,
+}
+
+impl CheckType {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::ReturnValue,
+            Self::ArgumentPrecondition,
+            Self::ArgumentPostcondition,
+            Self::Causality,
+        ]
+    }
+
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s {
+            "retval" => Some(Self::ReturnValue),
+            "arg.pre" => Some(Self::ArgumentPrecondition),
+            "arg.post" => Some(Self::ArgumentPostcondition),
+            "causality" => Some(Self::Causality),
+            _ => None,
+        }
+    }
+
+    // Also the key of the features checked
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ReturnValue => "retval",
+            Self::ArgumentPrecondition => "arg.pre",
+            Self::ArgumentPostcondition => "arg.post",
+            Self::Causality => "causality",
+        }
+    }
+
+    // Only the return values are checked for the functions with variable arguments, whose arguments
+    // cannot be handled properly
+    fn applies_to(&self, target: &str) -> bool {
+        *self == Self::ReturnValue || !is_variable_argument(target)
+    }
+}
+
+// The report of a violation, in the shape of the one of `lib/bug_report.py`
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct BugReport {
+    pub func_name: String,
+    // The features violating the specification
+    pub feature: serde_json::Value,
+    // The share of the traces having the features
+    pub frequency: f64,
+    #[serde(rename = "type")]
+    pub check_type: String,
+    pub alarm_text: String,
+    // The frequency in the specification backing the violated rule
+    pub support: Option<f64>,
+    // Only the specifications inferred from code are used, so the doc-related fields are always empty
+    pub doc_sentences: Option<serde_json::Value>,
+    pub doc_feature: Option<serde_json::Value>,
+}
+
+// The reports by the locations
+pub type BugReports = BTreeMap<String, Vec<BugReport>>;
+
+pub enum CheckResult {
+    Violation { alarm_text: String, support: Option<f64> },
+    Pass,
+    // Neither violating nor satisfying the specification, e.g., the return value is returned
+    Skip,
+}
+
+impl CheckResult {
+    fn from_alarms(alarms: Vec<(String, Option<f64>)>) -> Self {
+        if alarms.is_empty() {
+            Self::Pass
+        } else {
+            Self::Violation {
+                alarm_text: alarms.iter().map(|(text, _)| text.as_str()).collect(),
+                support: alarms
+                    .iter()
+                    .filter_map(|(_, support)| *support)
+                    .fold(None, |max, s| Some(max.map_or(s, |max: f64| max.max(s)))),
+            }
+        }
+    }
+}
+
+pub fn check_retval(target: &str, features: &TraceFeatures, spec: &ReturnValueSpecification) -> CheckResult {
+    let retval = match &features.retval {
+        Some(retval) => retval,
+        None => return CheckResult::Pass,
+    };
+    let mut need_check = false;
+    if spec.no_need_to_check_if_same_in_post {
+        let has_same_in_post = features
+            .causality
+            .as_ref()
+            .map_or(false, |causality| causality.post_call.contains_key(target));
+        if has_same_in_post {
+            return CheckResult::Skip;
+        } else if spec.no_same_in_post_need_to_check {
+            need_check = true;
+        }
+    }
+    if !need_check && !spec.need_to_check.0 {
+        return CheckResult::Pass;
+    }
+
+    let support = Some(spec.need_to_check.1);
+    match check_value(&retval.check) {
+        None => {
+            // Only tolerate the return values which are directly returned
+            if retval.ctx.returned {
+                CheckResult::Skip
+            } else if retval.ctx.derefed_read || retval.ctx.derefed_write {
+                CheckResult::Violation {
+                    alarm_text: "Dereferenced read/write the return value without check. ".to_string(),
+                    support,
+                }
+            } else {
+                CheckResult::Violation {
+                    alarm_text: "Lacking proper check for the return value. ".to_string(),
+                    support,
+                }
+            }
+        }
+        Some(chkval) => {
+            if spec.valid_chkvals.is_empty() || spec.valid_chkvals.contains_key(&chkval) {
+                CheckResult::Pass
+            } else {
+                CheckResult::Violation {
+                    alarm_text: "The check condition for the return value is potential wrong. ".to_string(),
+                    support,
+                }
+            }
+        }
+    }
+}
+
+pub fn check_arg_pre(target: &str, features: &TraceFeatures, spec: &ArgumentSpecification) -> CheckResult {
+    let arg_pre = match &features.arg_pre {
+        Some(arg_pre) => arg_pre,
+        None => return CheckResult::Pass,
+    };
+    if arg_pre.arg_num != spec.args_need_to_check.len() {
+        return CheckResult::Pass;
+    }
+    let mut alarms = vec![];
+    for (i, (arg, (need_to_check, score))) in arg_pre.feature.iter().zip(spec.args_need_to_check.iter()).enumerate() {
+        if !is_post(target) {
+            if *need_to_check && !arg.check.checked && !arg.is_global {
+                alarms.push((
+                    format!("violate the most-frequent check for arg.{}.pre. ", i),
+                    Some(*score),
+                ));
+            }
+        } else if arg.is_alloca {
+            // The functions finishing with the arguments, e.g., `free`
+            alarms.push((format!("Potential: arg {} is on stack and dealloced. ", i), None));
+        }
+    }
+    CheckResult::from_alarms(alarms)
+}
+
+pub fn check_arg_post(features: &TraceFeatures, spec: &ArgumentSpecification) -> CheckResult {
+    let arg_post = match &features.arg_post {
+        Some(arg_post) => arg_post,
+        None => return CheckResult::Pass,
+    };
+    // The features of the arguments are not of the same call if the numbers of arguments differ
+    if let Some(arg_pre) = &features.arg_pre {
+        if arg_pre.arg_num != arg_post.arg_num {
+            return CheckResult::Pass;
+        }
+    }
+    let mut alarms = vec![];
+    for (i, (arg, (need_to_check, score))) in arg_post.feature.iter().zip(spec.args_need_to_check.iter()).enumerate() {
+        let is_constant_or_global = features
+            .arg_pre
+            .as_ref()
+            .and_then(|arg_pre| arg_pre.feature.get(i))
+            .map_or(false, |arg_pre| arg_pre.is_constant || arg_pre.is_global);
+        if is_constant_or_global || arg.returned {
+            continue;
+        }
+        if *need_to_check && !arg.used_in_check {
+            let alarm_text = if arg.derefed_read || arg.derefed_write {
+                format!("Dereferenced without check of arg.{}.post", i)
+            } else {
+                format!("Potential lack check for arg.{}.post", i)
+            };
+            alarms.push((alarm_text, Some(*score)));
+        }
+    }
+    CheckResult::from_alarms(alarms)
+}
+
+pub fn check_causality(target: &str, features: &TraceFeatures, spec: &CausalitySpecification) -> CheckResult {
+    let causality = match &features.causality {
+        Some(causality) => causality,
+        None => return CheckResult::Pass,
+    };
+    let mut alarms = vec![];
+
+    // The pre functions are only checked for the functions used after others
+    if is_subsequent(target) {
+        let pre_functions = spec.pre_functions.iter().map(|(f, score)| (f, *score, None)).collect();
+        alarms.extend(check_causal_functions(
+            target,
+            CausalType::Pre,
+            &causality.pre_call,
+            pre_functions,
+            None,
+        ));
+    }
+
+    // Skip the post functions for the functions finishing with an object, and the return values not used at all
+    if !is_post(target) && ret_is_used(features) {
+        let post_functions = spec
+            .post_functions
+            .iter()
+            .map(|(f, (score, conds))| (f, *score, Some(conds)))
+            .collect();
+        let cond = chkval_cond(features);
+        alarms.extend(check_causal_functions(
+            target,
+            CausalType::Post,
+            &causality.post_call,
+            post_functions,
+            Some(&cond),
+        ));
+    } else if causality.post_call.len() == 1
+        && is_post(target)
+        && causality.post_call.contains_key(target)
+        && !causality.pre_call.contains_key(target)
+    {
+        // E.g., double free
+        alarms.push((format!("Potential: duplicated call of {} in post.call. ", target), None));
+    }
+    CheckResult::from_alarms(alarms)
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum CausalType {
+    Pre,
+    Post,
+}
+
+impl CausalType {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Pre => "pre.call",
+            Self::Post => "post.call",
+        }
+    }
+}
+
+// Whether the return value is stored somewhere, so that the post functions may be called elsewhere
+fn ret_is_global(features: &TraceFeatures) -> bool {
+    match &features.retval {
+        Some(retval) => retval.ctx.indir_returned || retval.ctx.returned || retval.ctx.stored_not_local,
+        None => false,
+    }
+}
+
+fn ret_is_used(features: &TraceFeatures) -> bool {
+    if ret_is_global(features) {
+        return false;
+    }
+    match &features.retval {
+        Some(retval) => {
+            retval.check.checked
+                || retval.check.indir_checked
+                || retval.ctx.derefed_read
+                || retval.ctx.derefed_write
+                || retval.ctx.used_in_bin
+                || retval.ctx.used_in_call
+        }
+        None => true,
+    }
+}
+
+// The functions in the specification, i.e., `(function, frequency, check conditions)`
+type CausalFunctions<'a> = Vec<(&'a String, f64, Option<&'a BTreeMap<String, f64>>)>;
+
+fn check_causal_functions(
+    target: &str,
+    causal_type: CausalType,
+    called: &BTreeMap<String, CausalityFuncFeatures>,
+    mut functions: CausalFunctions,
+    cond: Option<&String>,
+) -> Vec<(String, Option<f64>)> {
+    if ignore_causal(target, causal_type, called) {
+        return vec![];
+    }
+    // From the most frequent ones
+    functions.sort_by(|(f1, s1, _), (f2, s2, _)| s2.total_cmp(s1).then_with(|| f2.cmp(f1)));
+    let num_functions = functions.len();
+
+    let mut alarms = vec![];
+    for (func, score, conds) in functions {
+        // Always called along with the target
+        if score == 1.0 {
+            continue;
+        }
+        // Only under the check conditions where the post function is called
+        if let (Some(cond), Some(conds)) = (cond, conds) {
+            if !conds.is_empty() && !conds.contains_key(cond) {
+                continue;
+            }
+        }
+        // Consider the direct variants, e.g., `kfree_sensitive` for `kfree`
+        if !called.keys().any(|f| f.contains(func.as_str())) {
+            alarms.push((format!("Lack {}: {}. ", causal_type.name(), func), Some(score)));
+        }
+        // Only the most frequent one if there are many classes
+        if num_functions > 2 {
+            break;
+        }
+    }
+    alarms
+}
+
+// The target is paired with a called function by names, e.g., `foo_alloc` with `foo_free`
+fn ignore_causal(target: &str, causal_type: CausalType, called: &BTreeMap<String, CausalityFuncFeatures>) -> bool {
+    called.keys().any(|func| {
+        let related = match causal_type {
+            CausalType::Pre => is_pre(func),
+            CausalType::Post => is_post(func),
+        };
+        related && has_same_prefix(target, func, causal_type)
+    })
+}
+
+fn has_same_prefix(target: &str, func: &str, causal_type: CausalType) -> bool {
+    let (is_target_kind, is_func_kind): (fn(&str) -> bool, fn(&str) -> bool) = match causal_type {
+        CausalType::Pre => (is_subsequent, is_pre),
+        CausalType::Post => (is_pre_seq, is_post),
+    };
+    let suffix = |s: &str, i: usize| s.get(i..).unwrap_or("").to_string();
+    let prefix = |s: &str, i: usize| s.get(..i).unwrap_or(s).to_string();
+
+    // The position of the last `_`, or where the keywords begin
+    let length = target.len();
+    let mut idx = 0;
+    match target.rfind('_') {
+        Some(i) => idx = i,
+        None => {
+            for i in 0..length {
+                idx = i;
+                if !is_target_kind(&suffix(target, i)) {
+                    break;
+                }
+            }
+        }
+    }
+    idx < length
+        && (is_target_kind(&suffix(target, idx)) == is_func_kind(&suffix(func, idx))
+            || (idx > 0 && prefix(target, idx) == prefix(func, idx)))
+}
+
+// Check the features of a target against its specification
+pub fn detect_target(
+    target_features: &TargetFeatures,
+    spec: &Specification,
+    check_types: &[CheckType],
+) -> BTreeMap<&'static str, BugReports> {
+    let target = target_features.target.as_str();
+    let total_time = target_features.num_traces();
+    let mut reports: BTreeMap<&'static str, BugReports> = BTreeMap::new();
+    // The locations having correct traces are not reported for the return values
+    let mut tolerated: HashSet<&String> = HashSet::new();
+
+    for AggregatedFeatures { features, time, locs } in &target_features.features {
+        let frequency = (*time as f64 / total_time as f64 * 1000.0).round() / 1000.0;
+        let json = features.to_json();
+        for check_type in check_types {
+            let feature = match json.get(check_type.name()) {
+                Some(feature) if check_type.applies_to(target) => feature,
+                _ => continue,
+            };
+            let result = match check_type {
+                CheckType::ReturnValue => check_retval(target, features, &spec.ret),
+                CheckType::ArgumentPrecondition => check_arg_pre(target, features, &spec.arg_pre),
+                CheckType::ArgumentPostcondition => check_arg_post(features, &spec.arg_post),
+                CheckType::Causality => check_causality(target, features, &spec.causal),
+            };
+            let type_reports = reports.entry(check_type.name()).or_default();
+            match result {
+                CheckResult::Violation { alarm_text, support } => {
+                    let report = BugReport {
+                        func_name: target.to_string(),
+                        feature: feature.clone(),
+                        frequency,
+                        check_type: check_type.name().to_string(),
+                        alarm_text,
+                        support,
+                        doc_sentences: None,
+                        doc_feature: None,
+                    };
+                    for loc in locs {
+                        if *check_type == CheckType::ReturnValue && tolerated.contains(loc) {
+                            continue;
+                        }
+                        let loc_reports = type_reports.entry(loc.clone()).or_default();
+                        if !loc_reports.contains(&report) {
+                            loc_reports.push(report.clone());
+                        }
+                    }
+                }
+                CheckResult::Pass if *check_type == CheckType::ReturnValue => {
+                    for loc in locs {
+                        tolerated.insert(loc);
+                        type_reports.remove(loc);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    reports
+}
+
+// Check the targets having specifications, and gather the reports by locations
+pub fn detect(
+    storage: &dyn Storage,
+    specs: &BTreeMap<String, Specification>,
+    check_types: &[CheckType],
+    remove_dup: bool,
+) -> BugReports {
+    let target_reports = specs
+        .par_iter()
+        .map(|(target, spec)| {
+            let features = TargetFeatures::load(storage, target, remove_dup);
+            detect_target(&features, spec, check_types)
+        })
+        .collect::<Vec<_>>();
+
+    let mut reports = BugReports::new();
+    for check_type in check_types {
+        for type_reports in target_reports.iter().filter_map(|r| r.get(check_type.name())) {
+            for (loc, loc_reports) in type_reports {
+                reports
+                    .entry(loc.clone())
+                    .or_default()
+                    .extend(loc_reports.iter().cloned());
+            }
+        }
+    }
+    reports
+}
+
+// The text report, the same as the `bugreport.txt` of the python detector
+pub fn format_bug_reports(reports: &BugReports, only_report_locations: bool) -> String {
+    let mut text = String::new();
+    for (loc, loc_reports) in reports {
+        if only_report_locations {
+            let mut types = vec![];
+            for report in loc_reports {
+                if !types.contains(&report.check_type) {
+                    types.push(report.check_type.clone());
+                }
+            }
+            text += &format!("{}: {}, TYPE: {}\n", loc_reports[0].func_name, loc, types.join(" "));
+            continue;
+        }
+        text += &format!("{}:\n\tLocation:{}\n", loc_reports[0].func_name, loc);
+        let mut reported_features = vec![];
+        for report in loc_reports {
+            if reported_features.contains(&&report.feature) {
+                continue;
+            }
+            reported_features.push(&report.feature);
+            text += &format!(
+                "\tTYPE: {}. feature: {}\n\tViolation: {}\n",
+                report.check_type, report.feature, report.alarm_text
+            );
+            if loc_reports.len() > 1 {
+                text += "\n";
+            }
+        }
+        text += "\n";
+    }
+    text += &format!("Total reports: {}\n", reports.len());
+    text
+}
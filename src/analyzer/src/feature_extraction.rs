@@ -1,15 +1,15 @@
 use indicatif::*;
 use llir::{types::*, Module};
 use rayon::prelude::*;
-use serde::Deserialize;
-use std::collections::HashMap;
-use std::fs;
-use std::path::PathBuf;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
 use crate::call_graph::FunctionUtil;
 use crate::feature_extractors::*;
 use crate::options::*;
 use crate::semantics::boxed::*;
+use crate::storage::*;
 use crate::utils::*;
 
 #[derive(Deserialize)]
@@ -28,12 +28,27 @@ pub struct Instr {
     pub loc: String,
     pub sem: Semantics,
     pub res: Option<Value>,
+    // The source expressions of `res` and the operands of `sem`, e.g., `{"res": "req->len", "op1": "4"}`
+    #[serde(default)]
+    pub src: BTreeMap<String, String>,
+    // The depth of the call stack, which is 0 in the entry function of the slice
+    #[serde(default)]
+    pub depth: usize,
+}
+
+// The size in bytes of a stack buffer allocated on the path
+#[derive(Deserialize)]
+pub struct Capacity {
+    pub loc: Value,
+    pub size: u64,
 }
 
 #[derive(Deserialize)]
 pub struct Trace {
     pub target: usize,
     pub instrs: Vec<Instr>,
+    #[serde(default)]
+    pub capacities: Vec<Capacity>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -57,10 +72,45 @@ impl Trace {
         &self.instrs[self.target]
     }
 
+    // The `Ret` of the entry function ending the trace, which is missing if the trace is cut, e.g., at an
+    // `unreachable`. Only the return of the entry function ends the trace, not those of the callees
+    pub fn entry_ret(&self) -> Option<&Instr> {
+        self.instrs
+            .last()
+            .filter(|instr| instr.depth == 0 && matches!(instr.sem, Semantics::Ret { .. }))
+    }
+
     pub fn target_args(&self) -> Vec<&Value> {
         self.target_instr().sem.call_args()
     }
 
+    // The size in bytes of the stack buffer at the location, which may be decayed to its first element
+    pub fn capacity(&self, loc: &Value) -> Option<u64> {
+        match loc {
+            Value::GEP { loc, indices } if indices.iter().all(|i| **i == Value::Int(0)) => self.capacity(loc),
+            _ => self.capacities.iter().find(|c| &c.loc == loc).map(|c| c.size),
+        }
+    }
+
+    // The source expression of a value, taken from the first instruction naming it
+    pub fn source_expr(&self, value: &Value) -> Option<&str> {
+        for instr in &self.instrs {
+            if instr.res.as_ref() == Some(value) {
+                if let Some(expr) = instr.src.get("res") {
+                    return Some(expr);
+                }
+            }
+            for (key, op) in instr.sem.operands() {
+                if &**op == value {
+                    if let Some(expr) = instr.src.get(&key) {
+                        return Some(expr);
+                    }
+                }
+            }
+        }
+        None
+    }
+
     pub fn iter_instrs_from_target(&self, dir: TraceIterDirection) -> Vec<(usize, &Instr)> {
         self.iter_instrs_from(dir, self.target)
     }
@@ -74,14 +124,94 @@ impl Trace {
     }
 }
 
-pub trait FeatureExtractorOptions: IOOptions + Send + Sync {}
+// The functions checking error pointers returned by kernel APIs
+pub const DEFAULT_CHECKER_FUNCTIONS: [&str; 3] = ["IS_ERR", "IS_ERR_OR_NULL", "IS_ERR_VALUE"];
+
+// The instrumentation inserted by the sanitizers, which is not part of the program
+pub fn is_sanitizer_function(f: &str) -> bool {
+    f.contains("__asan") || f.contains("__sanitizer") || f.contains("__kasan")
+}
+
+pub trait FeatureExtractorOptions: IOOptions + Send + Sync {
+    // The functions whose call on the return value is regarded as an error-pointer check
+    fn checker_functions(&self) -> Vec<String> {
+        DEFAULT_CHECKER_FUNCTIONS.iter().map(|f| f.to_string()).collect()
+    }
+}
+
+// The version of the feature format, to be bumped on incompatible changes
+pub const FEATURE_VERSION: u32 = 2;
+
+// The features of a trace, keyed by the names of the extractors
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TraceFeatures {
+    pub version: u32,
+    pub loc: String,
+    #[serde(rename = "arg.pre", default, skip_serializing_if = "Option::is_none")]
+    pub arg_pre: Option<ArgumentPreconditionFeatures>,
+    #[serde(rename = "arg.post", default, skip_serializing_if = "Option::is_none")]
+    pub arg_post: Option<ArgumentPostconditionFeatures>,
+    #[serde(rename = "arg.bounds", default, skip_serializing_if = "Option::is_none")]
+    pub arg_bounds: Option<ArgumentBoundsFeatures>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub causality: Option<CausalityFeatures>,
+    // Missing if the target returns nothing or the return value is unknown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retval: Option<ReturnValueFeatures>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<ResourceFeatures>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bracket: Option<BracketFeatures>,
+}
+
+impl TraceFeatures {
+    pub fn new(loc: String) -> Self {
+        Self {
+            version: FEATURE_VERSION,
+            loc,
+            ..Default::default()
+        }
+    }
+
+    pub fn insert(&mut self, group: FeatureGroup) {
+        match group {
+            FeatureGroup::ArgumentPrecondition(features) => self.arg_pre = Some(features),
+            FeatureGroup::ArgumentPostcondition(features) => self.arg_post = Some(features),
+            FeatureGroup::ArgumentBounds(features) => self.arg_bounds = Some(features),
+            FeatureGroup::Causality(features) => self.causality = Some(features),
+            FeatureGroup::ReturnValue(features) => self.retval = features,
+            FeatureGroup::Resource(features) => self.resource = Some(features),
+            FeatureGroup::Bracket(features) => self.bracket = Some(features),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("Cannot turn features into json")
+    }
+
+    // The JSON Schema of the feature format
+    pub fn schema() -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(TraceFeatures)).expect("Cannot turn schema into json")
+    }
+}
+
+// The features extracted by an extractor
+pub enum FeatureGroup {
+    ArgumentPrecondition(ArgumentPreconditionFeatures),
+    ArgumentPostcondition(ArgumentPostconditionFeatures),
+    ArgumentBounds(ArgumentBoundsFeatures),
+    Causality(CausalityFeatures),
+    ReturnValue(Option<ReturnValueFeatures>),
+    Resource(ResourceFeatures),
+    Bracket(BracketFeatures),
+}
 
 pub trait FeatureExtractor: Send + Sync {
     fn name(&self) -> String;
 
     fn filter<'ctx>(&self, target_type: bool) -> bool;
 
-    fn extract(&self, slice_id: usize, slice: &Slice, trace: &Trace) -> serde_json::Value;
+    fn extract(&self, slice_id: usize, slice: &Slice, trace: &Trace) -> FeatureGroup;
 }
 
 pub struct FeatureExtractors {
@@ -89,20 +219,23 @@ pub struct FeatureExtractors {
 }
 
 impl FeatureExtractors {
-    pub fn all() -> Self {
+    pub fn all(options: &impl FeatureExtractorOptions) -> Self {
         Self {
             extractors: vec![
                 Box::new(ArgumentPreconditionFeatureExtractor::new()),
                 Box::new(ArgumentPostconditionFeatureExtractor::new()),
+                Box::new(ArgumentBoundsFeatureExtractor::new()),
                 Box::new(CausalityFeatureExtractor::new()),
-                Box::new(ReturnValueFeatureExtractor::new()),
+                Box::new(ReturnValueFeatureExtractor::new(options.checker_functions())),
+                Box::new(ResourceFeatureExtractor::new()),
+                Box::new(BracketFeatureExtractor::new()),
             ],
         }
     }
 
-    pub fn extractors_for_target<'ctx>(has_return_type: bool) -> Self {
+    pub fn extractors_for_target<'ctx>(has_return_type: bool, options: &impl FeatureExtractorOptions) -> Self {
         Self {
-            extractors: Self::all()
+            extractors: Self::all(options)
                 .extractors
                 .into_iter()
                 .filter(|extractor| extractor.filter(has_return_type))
@@ -110,22 +243,37 @@ impl FeatureExtractors {
         }
     }
 
-    pub fn extract_features(&self, slice_id: usize, slice: &Slice, trace: &Trace) -> serde_json::Value {
-        let mut map = serde_json::Map::new();
+    pub fn extract_features(&self, slice_id: usize, slice: &Slice, trace: &Trace) -> TraceFeatures {
         // Put the loc into feature file.
-        map.insert(
-            "loc".to_string(),
-            serde_json::Value::String(trace.target_instr().loc.clone()),
-        );
+        let mut features = TraceFeatures::new(trace.target_instr().loc.clone());
         // Acquire feature from each extractor.
         for extractor in &self.extractors {
-            map.insert(extractor.name(), extractor.extract(slice_id, &slice, &trace));
+            features.insert(extractor.extract(slice_id, &slice, &trace));
         }
-        serde_json::Value::Object(map)
+        features
     }
 
-    pub fn load_trace(path: &PathBuf) -> Result<Trace, String> {
-        load_json_t(path)
+    pub fn load_trace(
+        storage: &dyn Storage,
+        target: &str,
+        package: Option<&str>,
+        slice_id: usize,
+        trace_id: usize,
+    ) -> Result<Trace, String> {
+        storage
+            .load_trace(target, package, slice_id, trace_id)
+            .and_then(|json| serde_json::from_value(json).map_err(|x| format!("Cannot parse trace: {:?}", x)))
+    }
+
+    pub fn load_slice(
+        storage: &dyn Storage,
+        target: &str,
+        package: Option<&str>,
+        slice_id: usize,
+    ) -> Result<Slice, String> {
+        storage
+            .load_slice(target, package, slice_id)
+            .and_then(|json| serde_json::from_value(json).map_err(|x| format!("Cannot parse slice: {:?}", x)))
     }
 }
 
@@ -150,6 +298,7 @@ where
     O: FeatureExtractorOptions + IOOptions,
 {
     pub options: &'a O,
+    pub storage: &'a dyn Storage,
     pub occurrences: HashMap<String, (bool, usize)>,
     pub func_types: HashMap<String, FunctionType<'ctx>>,
 }
@@ -162,10 +311,12 @@ where
         module: &'a Module<'ctx>,
         occurrences: HashMap<String, (bool, usize)>,
         options: &'a O,
+        storage: &'a dyn Storage,
     ) -> Result<Self, String> {
         let func_types = module.function_types();
         Ok(Self {
             options,
+            storage,
             occurrences,
             func_types,
         })
@@ -176,29 +327,24 @@ where
             .collect::<Vec<_>>()
             .into_par_iter()
             .map(|slice_id| {
-                let path = self.options.slice_target_file_path(func.as_str(), slice_id);
-                load_json_t(&path).expect("Cannot load slice files")
+                FeatureExtractors::load_slice(
+                    self.storage,
+                    func.as_str(),
+                    self.options.basename_of_bc_file(),
+                    slice_id,
+                )
+                .expect("Cannot load slice files")
             })
             .collect::<Vec<_>>()
     }
 
-    pub fn load_trace_file_paths(&self, func: &String, slice_id: usize) -> Vec<(usize, PathBuf)> {
-        match fs::read_dir(self.options.trace_target_slice_dir(func.as_str(), slice_id)) {
-            Ok(paths) => paths
-                .map(|path| {
-                    let path = path.expect("Cannot read traces folder path").path();
-                    let trace_id = path.file_stem().unwrap().to_str().unwrap().parse::<usize>().unwrap();
-                    (trace_id, path)
-                })
-                .collect::<Vec<_>>(),
-            _ => vec![],
-        }
+    pub fn load_trace_ids(&self, func: &String, slice_id: usize) -> Vec<usize> {
+        self.storage
+            .trace_ids(func.as_str(), self.options.basename_of_bc_file(), slice_id)
     }
 
     // Just for a specific bc-file(package)
     pub fn extract_features(&self, _: &mut LoggingContext) {
-        fs::create_dir_all(self.options.feature_dir()).expect("Cannot create features directory");
-
         let occurs_pb_style = ProgressStyle::default_bar()
             .template("[{elapsed_precise}] {bar:50.green/white} {pos:>5}/{len:5} {percent}% {msg}")
             .progress_chars("##-");
@@ -210,19 +356,15 @@ where
             .progress_with(occurs_pb)
             .for_each(|(func, (has_return_type, num_slices))| {
                 // Initialize extractors
-                let extractors = FeatureExtractors::extractors_for_target(*has_return_type);
+                let extractors = FeatureExtractors::extractors_for_target(*has_return_type, self.options);
 
                 // Load slices
                 let slices = self.load_slices(&func, *num_slices);
 
                 // Extract features
                 slices.par_iter().enumerate().for_each(|(slice_id, slice)| {
-                    // First create directory
-                    fs::create_dir_all(self.options.feature_target_slice_dir(func.as_str(), slice_id))
-                        .expect("Cannot create features func slice directory");
-
-                    // Then load trace file directories
-                    let traces = self.load_trace_file_paths(&func, slice_id);
+                    // Load the ids of traces
+                    let traces = self.load_trace_ids(&func, slice_id);
 
                     // let style = ProgressStyle::default_bar()
                     // 	.template(
@@ -241,18 +383,19 @@ where
                     traces
                         .into_par_iter()
                         // .progress_with(pb)
-                        .for_each(|(trace_id, dir_entry)| {
+                        .for_each(|trace_id| {
                             // Load trace json
-                            let trace = FeatureExtractors::load_trace(&dir_entry);
+                            let package = self.options.basename_of_bc_file();
+                            let trace =
+                                FeatureExtractors::load_trace(self.storage, func.as_str(), package, slice_id, trace_id);
 
                             match trace {
                                 Ok(trace) => {
                                     // Extract and dump features
                                     let features = extractors.extract_features(slice_id, &slice, &trace);
-                                    let path =
-                                        self.options
-                                            .feature_target_slice_file_path(func.as_str(), slice_id, trace_id);
-                                    dump_json(&features, path).expect("Cannot dump features json");
+                                    self.storage
+                                        .dump_features(func.as_str(), package, slice_id, trace_id, &features.to_json())
+                                        .expect("Cannot dump features json");
                                 }
                                 _ => {}
                             }
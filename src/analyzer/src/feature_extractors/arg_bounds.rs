@@ -0,0 +1,322 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::feature_extraction::*;
+use crate::feature_extractors::retval::get_br_cond;
+use crate::semantics::boxed::*;
+use crate::semantics::*;
+
+// What an argument is compared against
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Bound {
+    Const { value: i64 },
+    // Another argument of the target
+    Arg { arg: usize },
+    // A field of (the object pointed by) another argument, e.g., `len <= buf->size`
+    FieldOfArg { arg: usize },
+    // The result of a size-like call, e.g., `len <= strlen(src)`
+    SizeCall { func: String },
+    // The size in bytes of the buffer pointed by another argument, e.g., `len <= sizeof(buf)` for `char buf[16]`
+    Capacity { arg: usize },
+    Other,
+}
+
+// `arg pred bound`, where `pred` is the one holding on the path, or `None` if the branch is unknown
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BoundComparison {
+    pub pred: Option<String>,
+    // Whether the comparison is unsigned, e.g., `ult`
+    #[serde(default)]
+    pub unsigned: bool,
+    pub bound: Bound,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct Interval<T> {
+    pub lo: Option<T>,
+    pub hi: Option<T>,
+}
+
+impl<T: Copy + Ord> Interval<T> {
+    fn raise_lo(&mut self, lo: T) {
+        self.lo = Some(self.lo.map_or(lo, |l| l.max(lo)));
+    }
+
+    fn lower_hi(&mut self, hi: T) {
+        self.hi = Some(self.hi.map_or(hi, |h| h.min(hi)));
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ArgumentBoundsArgFeatures {
+    pub is_int: bool,
+    pub comparisons: Vec<BoundComparison>,
+    // The interval from the signed comparisons with constants
+    pub interval: Interval<i64>,
+    // The interval from the unsigned comparisons with constants
+    #[serde(default)]
+    pub unsigned_interval: Interval<u64>,
+    pub excluded: Vec<i64>,
+    // The relations with other arguments or sizes
+    pub relations: Vec<BoundComparison>,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ArgumentBoundsFeatures {
+    pub feature: Vec<ArgumentBoundsArgFeatures>,
+    pub arg_num: usize,
+}
+
+pub struct ArgumentBoundsFeatureExtractor;
+
+impl ArgumentBoundsFeatureExtractor {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl FeatureExtractor for ArgumentBoundsFeatureExtractor {
+    fn name(&self) -> String {
+        "arg.bounds".to_string()
+    }
+
+    fn filter<'ctx>(&self, _: bool) -> bool {
+        true
+    }
+
+    fn extract(&self, _: usize, _: &Slice, trace: &Trace) -> FeatureGroup {
+        let arguments = trace.target_args();
+
+        // The locations of loaded values, for finding the fields compared with
+        let mut loaded_from = HashMap::new();
+        for (_, instr) in trace.iter_instrs_from_target(TraceIterDirection::Backward) {
+            if let (Semantics::Load { loc }, Some(res)) = (&instr.sem, &instr.res) {
+                loaded_from.insert(res, &**loc);
+            }
+        }
+
+        // The sizes of the buffers passed to the target, for relating the compared constants to them
+        let capacities = arguments.iter().map(|arg| capacity(trace, arg)).collect::<Vec<_>>();
+
+        let features = arguments
+            .iter()
+            .map(|arg| {
+                if !may_be_integer(arg) {
+                    return ArgumentBoundsArgFeatures::default();
+                }
+
+                // The comparisons holding on the path: `arg pred bound`
+                let mut comparisons = vec![];
+                for (instr_i, instr) in trace.iter_instrs_from_target(TraceIterDirection::Backward) {
+                    if let Semantics::ICmp { pred, op0, op1 } = &instr.sem {
+                        let (other, swapped) = if &**op0 == *arg {
+                            (&**op1, false)
+                        } else if &**op1 == *arg {
+                            (&**op0, true)
+                        } else {
+                            continue;
+                        };
+                        let holding_pred = branch_after(trace, instr_i, instr).map(|br| {
+                            let pred = get_br_cond(*pred, br);
+                            if swapped {
+                                swap_pred(&pred).to_string()
+                            } else {
+                                pred
+                            }
+                        });
+                        let bound = bound_of_value(other, &arguments, &loaded_from);
+                        comparisons.push(BoundComparison {
+                            pred: holding_pred,
+                            unsigned: is_unsigned(*pred),
+                            bound,
+                        });
+                    }
+                }
+
+                // Summarize the comparisons into the intervals and the relations with other values. The
+                // signed and unsigned comparisons are kept apart, as a negative value is large when unsigned
+                let mut interval = Interval::<i64>::default();
+                let mut unsigned_interval = Interval::<u64>::default();
+                let mut excluded = BTreeSet::new();
+                let mut relations = BTreeSet::new();
+                for BoundComparison { pred, unsigned, bound } in &comparisons {
+                    let pred = match pred {
+                        Some(pred) => pred.as_str(),
+                        None => continue,
+                    };
+                    match *bound {
+                        Bound::Const { value: num } => {
+                            match (pred, *unsigned) {
+                                ("eq", _) => {
+                                    interval.raise_lo(num);
+                                    interval.lower_hi(num);
+                                    if num >= 0 {
+                                        unsigned_interval.raise_lo(num as u64);
+                                        unsigned_interval.lower_hi(num as u64);
+                                    }
+                                }
+                                ("ne", _) => {
+                                    excluded.insert(num);
+                                }
+                                ("lt", false) => interval.lower_hi(num.saturating_sub(1)),
+                                ("le", false) => interval.lower_hi(num),
+                                ("gt", false) => interval.raise_lo(num.saturating_add(1)),
+                                ("ge", false) => interval.raise_lo(num),
+                                ("lt", true) => unsigned_interval.lower_hi((num as u64).saturating_sub(1)),
+                                ("le", true) => unsigned_interval.lower_hi(num as u64),
+                                ("gt", true) => unsigned_interval.raise_lo((num as u64).saturating_add(1)),
+                                ("ge", true) => unsigned_interval.raise_lo(num as u64),
+                                _ => {}
+                            }
+
+                            // The constant may be the size of a buffer passed to the target, e.g., `len <= 16`
+                            // for `char buf[16]`
+                            if let Some((pred, num)) = inclusive_bound(pred, num) {
+                                for (j, cap) in capacities.iter().enumerate() {
+                                    if num >= 0 && *cap == Some(num as u64) {
+                                        relations.insert((pred.to_string(), *unsigned, Bound::Capacity { arg: j }));
+                                    }
+                                }
+                            }
+                        }
+                        Bound::Other => {}
+                        _ => {
+                            relations.insert((pred.to_string(), *unsigned, bound.clone()));
+                        }
+                    }
+                }
+
+                ArgumentBoundsArgFeatures {
+                    is_int: true,
+                    comparisons,
+                    interval,
+                    unsigned_interval,
+                    excluded: excluded.into_iter().collect(),
+                    relations: relations
+                        .into_iter()
+                        .map(|(pred, unsigned, bound)| BoundComparison {
+                            pred: Some(pred),
+                            unsigned,
+                            bound,
+                        })
+                        .collect(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        FeatureGroup::ArgumentBounds(ArgumentBoundsFeatures {
+            feature: features,
+            arg_num: arguments.len(),
+        })
+    }
+}
+
+// Pointers and constants which are not integers are not checked
+fn may_be_integer(arg: &Value) -> bool {
+    match arg {
+        Value::Alloc(_) | Value::GEP { .. } | Value::Glob(_) | Value::Func(_) | Value::FuncPtr | Value::Asm => false,
+        Value::Null | Value::Unknown => false,
+        _ => true,
+    }
+}
+
+// The branch taken on the comparison. Only go 5 steps forward as in `arg.pre`
+fn branch_after<'a>(trace: &'a Trace, instr_i: usize, icmp: &Instr) -> Option<&'a Branch> {
+    for (_, instr) in trace
+        .iter_instrs_from(TraceIterDirection::Forward, instr_i)
+        .into_iter()
+        .take(5)
+    {
+        if let Semantics::CondBr { cond, br } = &instr.sem {
+            if Some(&**cond) == icmp.res.as_ref() {
+                return Some(br);
+            }
+        }
+    }
+    None
+}
+
+fn is_unsigned(pred: Predicate) -> bool {
+    matches!(pred, Predicate::UGE | Predicate::UGT | Predicate::ULE | Predicate::ULT)
+}
+
+// The comparison with a constant as an inclusive one, e.g., `x < 17` as `x <= 16`
+fn inclusive_bound(pred: &str, num: i64) -> Option<(&str, i64)> {
+    match pred {
+        "lt" => Some(("le", num.checked_sub(1)?)),
+        "gt" => Some(("ge", num.checked_add(1)?)),
+        "le" | "ge" | "eq" => Some((pred, num)),
+        _ => None,
+    }
+}
+
+fn swap_pred(pred: &str) -> &str {
+    match pred {
+        "lt" => "gt",
+        "le" => "ge",
+        "gt" => "lt",
+        "ge" => "le",
+        pred => pred,
+    }
+}
+
+fn bound_of_value(value: &Value, arguments: &Vec<&Value>, loaded_from: &HashMap<&Value, &Value>) -> Bound {
+    if let Value::Int(num) = value {
+        return Bound::Const { value: *num };
+    }
+    if let Some(j) = arguments.iter().position(|arg| *arg == value) {
+        return Bound::Arg { arg: j };
+    }
+    match value {
+        Value::Call { func, .. } => match &**func {
+            Value::Func(f) if is_size_function(f) => Bound::SizeCall { func: f.clone() },
+            _ => Bound::Other,
+        },
+        _ => match loaded_from.get(value) {
+            Some(Value::GEP { loc, .. }) => match arguments.iter().position(|arg| *arg == &**loc) {
+                Some(j) => Bound::FieldOfArg { arg: j },
+                None => Bound::Other,
+            },
+            _ => Bound::Other,
+        },
+    }
+}
+
+const SIZE_FUNCTIONS: [&str; 5] = ["strlen", "strnlen", "wcslen", "wcsnlen", "ksize"];
+
+const SIZE_FUNCTION_TOKENS: [&str; 3] = ["len", "length", "size"];
+
+// The functions returning a length or a size, e.g., `strlen` and `get_frame_size`, matched by whole
+// `_`-separated tokens so that, e.g., `resize_buffer` is not one
+fn is_size_function(f: &str) -> bool {
+    SIZE_FUNCTIONS.contains(&f) || f.split('_').any(|token| SIZE_FUNCTION_TOKENS.contains(&token))
+}
+
+// The size in bytes of the buffer at the location, either allocated on the stack or by an allocator
+// called with constant sizes
+fn capacity(trace: &Trace, loc: &Value) -> Option<u64> {
+    match loc {
+        Value::Call { func, args, .. } => match &**func {
+            Value::Func(f) => allocation_size(f, args),
+            _ => None,
+        },
+        _ => trace.capacity(loc),
+    }
+}
+
+fn allocation_size(func: &str, args: &[Box<Value>]) -> Option<u64> {
+    let size_arg = |i: usize| match args.get(i).map(|arg| &**arg) {
+        Some(Value::Int(num)) if *num >= 0 => Some(*num as u64),
+        _ => None,
+    };
+    match func {
+        "malloc" | "kmalloc" | "kzalloc" | "vmalloc" | "vzalloc" | "kvmalloc" | "kvzalloc" => size_arg(0),
+        "kmalloc_node" | "kzalloc_node" => size_arg(0),
+        "devm_kmalloc" | "devm_kzalloc" => size_arg(1),
+        "calloc" | "kcalloc" | "kmalloc_array" | "kvcalloc" => size_arg(0)?.checked_mul(size_arg(1)?),
+        "devm_kcalloc" | "devm_kmalloc_array" => size_arg(1)?.checked_mul(size_arg(2)?),
+        _ => None,
+    }
+}
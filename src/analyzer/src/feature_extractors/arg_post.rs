@@ -1,10 +1,49 @@
-use serde_json::json;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
 use crate::feature_extraction::*;
 use crate::feature_extractors::arg_pre::get_args_to_check;
 use crate::semantics::boxed::*;
 
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UseKind {
+    Load,
+    Store,
+    Call,
+    Compare,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct UsedAfterCall {
+    pub used: bool,
+    // `None` if not used
+    pub first_use: Option<UseKind>,
+    pub load: bool,
+    pub store: bool,
+    pub call: bool,
+    pub compare: bool,
+    pub reassigned: bool,
+    pub nulled_first: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ArgumentPostconditionArgFeatures {
+    pub used_in_check: bool,
+    pub derefed_read: bool,
+    pub derefed_write: bool,
+    pub returned: bool,
+    pub indir_returned: bool,
+    pub used_after_call: UsedAfterCall,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ArgumentPostconditionFeatures {
+    pub feature: Vec<ArgumentPostconditionArgFeatures>,
+    pub arg_num: usize,
+}
+
 pub struct ArgumentPostconditionFeatureExtractor;
 
 impl ArgumentPostconditionFeatureExtractor {
@@ -22,7 +61,7 @@ impl FeatureExtractor for ArgumentPostconditionFeatureExtractor {
         true
     }
 
-    fn extract(&self, _: usize, _: &Slice, trace: &Trace) -> serde_json::Value {
+    fn extract(&self, _: usize, _: &Slice, trace: &Trace) -> FeatureGroup {
         let mut used_in_check = vec![]; // false;
         let mut derefed_read = vec![]; // false;
         let mut derefed_write = vec![];
@@ -139,15 +178,113 @@ impl FeatureExtractor for ArgumentPostconditionFeatureExtractor {
             }
         }
 
-        json!({
-            "feature": arguments.iter().enumerate().map(|(i, _)| json!({
-                "used_in_check": used_in_check[i],
-                "derefed_read": derefed_read[i],
-                "derefed_write": derefed_write[i],
-                "returned": returned[i],
-                "indir_returned": indir_returned[i],
-            })).collect::<Vec<_>>(),
-            "arg_num": arguments.len(),
+        FeatureGroup::ArgumentPostcondition(ArgumentPostconditionFeatures {
+            feature: arguments
+                .iter()
+                .enumerate()
+                .map(|(i, arg)| ArgumentPostconditionArgFeatures {
+                    used_in_check: used_in_check[i],
+                    derefed_read: derefed_read[i],
+                    derefed_write: derefed_write[i],
+                    returned: returned[i],
+                    indir_returned: indir_returned[i],
+                    used_after_call: get_used_after_call(trace, arg),
+                })
+                .collect(),
+            arg_num: arguments.len(),
         })
     }
 }
+
+// How the argument is used after the call, e.g., after released by `free`. `home` is the location
+// the argument is loaded from, which may be reassigned or set to null after the call
+fn get_used_after_call(trace: &Trace, arg: &Value) -> UsedAfterCall {
+    let mut first_use = None;
+    let mut load = false;
+    let mut store = false;
+    let mut call = false;
+    let mut compare = false;
+    let mut reassigned = false;
+    let mut nulled_first = false;
+
+    match arg {
+        Value::Null | Value::Int(_) | Value::ConstSym(_) | Value::Func(_) | Value::Asm | Value::Unknown => {
+            return UsedAfterCall::default();
+        }
+        _ => {}
+    }
+
+    // The home location of the argument
+    let home = trace
+        .iter_instrs_from_target(TraceIterDirection::Backward)
+        .into_iter()
+        .find_map(|(_, instr)| match (&instr.sem, &instr.res) {
+            (Semantics::Load { loc }, Some(res)) if res == arg => Some(&**loc),
+            _ => None,
+        });
+
+    // Pointers derived from the argument, e.g., `&p->field`
+    let mut child_ptrs: HashSet<Value> = HashSet::new();
+    let is_arg = |v: &Value, child_ptrs: &HashSet<Value>| v == arg || child_ptrs.contains(v);
+
+    for (_, instr) in trace.iter_instrs_from_target(TraceIterDirection::Forward) {
+        let mut used = None;
+        match &instr.sem {
+            Semantics::Load { loc } => {
+                if is_arg(loc, &child_ptrs) {
+                    load = true;
+                    used = Some(UseKind::Load);
+                }
+            }
+            Semantics::Store { loc, val } => {
+                if is_arg(loc, &child_ptrs) {
+                    store = true;
+                    used = Some(UseKind::Store);
+                } else if Some(&**loc) == home {
+                    if **val == Value::Null && first_use.is_none() && !reassigned {
+                        nulled_first = true;
+                    } else if **val != Value::Null {
+                        reassigned = true;
+                    }
+                }
+            }
+            Semantics::Call { func, args } => {
+                let ignored = match &**func {
+                    Value::Func(f) => is_sanitizer_function(f),
+                    _ => false,
+                };
+                if !ignored && args.iter().find(|a| is_arg(a, &child_ptrs)).is_some() {
+                    call = true;
+                    used = Some(UseKind::Call);
+                }
+            }
+            Semantics::ICmp { op0, op1, .. } => {
+                if is_arg(op0, &child_ptrs) || is_arg(op1, &child_ptrs) {
+                    compare = true;
+                    used = Some(UseKind::Compare);
+                }
+            }
+            Semantics::GEP { loc, .. } => {
+                if is_arg(loc, &child_ptrs) {
+                    // GEP only performs address calculation and does not access memory
+                    child_ptrs.insert(instr.res.clone().unwrap());
+                }
+            }
+            _ => {}
+        }
+        if first_use.is_none() {
+            first_use = used;
+        }
+    }
+
+    UsedAfterCall {
+        used: load || store || call || compare,
+        first_use,
+        load,
+        store,
+        call,
+        compare,
+        reassigned,
+        nulled_first,
+    }
+}
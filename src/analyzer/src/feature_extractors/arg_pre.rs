@@ -1,11 +1,70 @@
-use serde_json::json;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
 use crate::feature_extraction::*;
 use crate::feature_extractors::retval::get_br_cond;
+use crate::feature_extractors::retval::get_switch_cond;
 use crate::feature_extractors::retval::num_of_value;
 use crate::semantics::boxed::*;
 
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ArgumentCheck {
+    pub checked: bool,
+    pub compared_with_const: i64,
+    pub compared_with_non_const: bool,
+    pub check_cond: String,
+    pub checked_consts: Vec<i64>,
+}
+
+// The nullness of a pointer argument on the path
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Nullness {
+    Null,
+    NonNull,
+    MaybeNull,
+    Unknown,
+}
+
+// Why the nullness of the argument is decided
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NullnessReason {
+    Constant,
+    Address,
+    NotPointer,
+    Checked,
+    UncheckedCallResult,
+    NoEvidence,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ArgumentNullness {
+    pub value: Nullness,
+    pub reason: NullnessReason,
+    // The function returning the argument, if unchecked
+    pub func: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ArgumentPreconditionArgFeatures {
+    pub check: ArgumentCheck,
+    pub is_constant: bool,
+    pub is_alloca: bool,
+    pub is_global: bool,
+    pub arg_value: i64,
+    pub nullness: ArgumentNullness,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ArgumentPreconditionFeatures {
+    pub has_relation: bool,
+    pub relations: Vec<Vec<usize>>,
+    pub arg_num: usize,
+    pub feature: Vec<ArgumentPreconditionArgFeatures>,
+}
+
 pub struct ArgumentPreconditionFeatureExtractor;
 
 impl ArgumentPreconditionFeatureExtractor {
@@ -23,13 +82,14 @@ impl FeatureExtractor for ArgumentPreconditionFeatureExtractor {
         true
     }
 
-    fn extract(&self, _: usize, _: &Slice, trace: &Trace) -> serde_json::Value {
+    fn extract(&self, _: usize, _: &Slice, trace: &Trace) -> FeatureGroup {
         let mut has_relation = false; // if there are some realtionships between each arguments
         let mut relations = vec![]; // record the relations between each arguments
         let mut checked = vec![]; // false;
         let mut compared_with_const = vec![]; // 0;
         let mut compared_with_non_const = vec![]; // false;
         let mut arg_check_cond = vec![]; // "ne" | "eq" ...
+        let mut checked_consts = vec![];
         let mut is_constant = vec![]; // false;
         let mut is_alloca = vec![]; // false;
         let mut is_global = vec![];
@@ -43,6 +103,7 @@ impl FeatureExtractor for ArgumentPreconditionFeatureExtractor {
             compared_with_const.push(0);
             compared_with_non_const.push(false);
             arg_check_cond.push("".to_string());
+            checked_consts.push(vec![]);
             is_constant.push(false);
             is_alloca.push(false);
             is_global.push(false);
@@ -89,6 +150,7 @@ impl FeatureExtractor for ArgumentPreconditionFeatureExtractor {
                                 let op1_num = num_of_value(&op1);
                                 if let Some(num) = op0_num.or(op1_num) {
                                     compared_with_const[i] = num;
+                                    checked_consts[i] = vec![num];
 
                                     // Search for a branch instruction after the icmp. Only go 5 steps forward.
                                     for (_, maybe_br) in trace
@@ -112,30 +174,118 @@ impl FeatureExtractor for ArgumentPreconditionFeatureExtractor {
                         }
                     }
                 }
+                Semantics::Switch {
+                    cond,
+                    cases,
+                    taken_cases,
+                } => {
+                    for (i, args) in args_to_check.iter().enumerate() {
+                        if is_constant[i] {
+                            continue;
+                        }
+                        if args.contains(&**cond) {
+                            checked[i] = true;
+                            let (num, cond, consts) = get_switch_cond(cases, taken_cases);
+                            compared_with_const[i] = num;
+                            arg_check_cond[i] = cond;
+                            checked_consts[i] = consts;
+                        }
+                    }
+                }
                 _ => {}
             }
         }
 
-        json!({
-            "has_relation": has_relation,
-            "relations": relations,
-            "arg_num": arguments.len(),
-            "feature": arguments.iter().enumerate().map(|(i, _)| json!({
-                "check": {
-                    "checked": checked[i],
-                    "compared_with_const": compared_with_const[i],
-                    "compared_with_non_const": compared_with_non_const[i],
-                    "check_cond": arg_check_cond[i],
-                },
-                "is_constant": is_constant[i],
-                "is_alloca": is_alloca[i],
-                "is_global": is_global[i],
-                "arg_value": arg_value[i],
-            })).collect::<Vec<_>>(),
+        FeatureGroup::ArgumentPrecondition(ArgumentPreconditionFeatures {
+            has_relation,
+            relations,
+            arg_num: arguments.len(),
+            feature: arguments
+                .iter()
+                .enumerate()
+                .map(|(i, arg)| ArgumentPreconditionArgFeatures {
+                    check: ArgumentCheck {
+                        checked: checked[i],
+                        compared_with_const: compared_with_const[i],
+                        compared_with_non_const: compared_with_non_const[i],
+                        check_cond: arg_check_cond[i].clone(),
+                        checked_consts: checked_consts[i].clone(),
+                    },
+                    is_constant: is_constant[i],
+                    is_alloca: is_alloca[i],
+                    is_global: is_global[i],
+                    arg_value: arg_value[i],
+                    nullness: get_arg_nullness(trace, arg),
+                })
+                .collect(),
         })
     }
 }
 
+// The base pointer of the argument, e.g., `p` for `&p->field`
+fn base_pointer(arg: &Value) -> &Value {
+    match arg {
+        Value::GEP { loc, .. } => base_pointer(loc),
+        _ => arg,
+    }
+}
+
+fn get_arg_nullness(trace: &Trace, arg: &Value) -> ArgumentNullness {
+    let base = base_pointer(arg);
+    let (nullness, reason, func) = match base {
+        Value::Null => (Nullness::Null, NullnessReason::Constant, None),
+        Value::Alloc(_) | Value::Glob(_) | Value::Func(_) => (Nullness::NonNull, NullnessReason::Address, None),
+        Value::Int(_) | Value::ConstSym(_) | Value::Asm => (Nullness::Unknown, NullnessReason::NotPointer, None),
+        _ => {
+            // The latest check against null on the path decides the nullness
+            let checked = trace
+                .iter_instrs_from_target(TraceIterDirection::Backward)
+                .into_iter()
+                .find_map(|(instr_i, instr)| match &instr.sem {
+                    Semantics::ICmp { pred, op0, op1 } => {
+                        let is_null = |v: &Value| v == &Value::Null || v == &Value::Int(0);
+                        let is_arg = |v: &Value| v == arg || v == base;
+                        if !((is_arg(op0) && is_null(op1)) || (is_arg(op1) && is_null(op0))) {
+                            return None;
+                        }
+                        let res = instr.res.as_ref()?;
+                        trace
+                            .iter_instrs_from(TraceIterDirection::Forward, instr_i)
+                            .into_iter()
+                            .take(5)
+                            .find_map(|(_, maybe_br)| match &maybe_br.sem {
+                                Semantics::CondBr { cond, br } if &**cond == res => {
+                                    match get_br_cond(*pred, br).as_str() {
+                                        "eq" => Some(Nullness::Null),
+                                        "ne" => Some(Nullness::NonNull),
+                                        _ => None,
+                                    }
+                                }
+                                _ => None,
+                            })
+                    }
+                    _ => None,
+                });
+            match (checked, base) {
+                (Some(nullness), _) => (nullness, NullnessReason::Checked, None),
+                (None, Value::Call { func, .. }) => {
+                    let func = match &**func {
+                        Value::Func(f) => Some(f.clone()),
+                        _ => None,
+                    };
+                    (Nullness::MaybeNull, NullnessReason::UncheckedCallResult, func)
+                }
+                (None, _) => (Nullness::Unknown, NullnessReason::NoEvidence, None),
+            }
+        }
+    };
+    ArgumentNullness {
+        value: nullness,
+        reason,
+        func,
+    }
+}
+
 fn arg_to_check(arg: &Value, depth: usize) -> Vec<Value> {
     if depth == 0 {
         vec![]
@@ -219,6 +369,14 @@ fn get_argval(arg: &Value, depth: usize) -> HashSet<Value> {
                     }
                 }
             }
+            Value::Select { true_val, false_val, .. } => {
+                for v in get_argval(&*true_val, depth - 1).iter() {
+                    val.insert(v.clone());
+                }
+                for v in get_argval(&*false_val, depth - 1).iter() {
+                    val.insert(v.clone());
+                }
+            }
             Value::Unknown => {}
             _ => {
                 val.insert(arg.clone());
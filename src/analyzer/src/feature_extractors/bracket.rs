@@ -0,0 +1,203 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+
+use crate::feature_extraction::*;
+use crate::semantics::boxed::*;
+
+// The functions known to open and close a bracket
+const BRACKET_FUNCTIONS: [(&str, &str); 22] = [
+    ("mutex_lock", "mutex_unlock"),
+    ("mutex_lock_interruptible", "mutex_unlock"),
+    ("mutex_lock_killable", "mutex_unlock"),
+    ("spin_lock", "spin_unlock"),
+    ("spin_lock_bh", "spin_unlock_bh"),
+    ("spin_lock_irq", "spin_unlock_irq"),
+    ("spin_lock_irqsave", "spin_unlock_irqrestore"),
+    ("raw_spin_lock", "raw_spin_unlock"),
+    ("raw_spin_lock_irqsave", "raw_spin_unlock_irqrestore"),
+    ("read_lock", "read_unlock"),
+    ("write_lock", "write_unlock"),
+    ("down", "up"),
+    ("down_read", "up_read"),
+    ("down_write", "up_write"),
+    ("rcu_read_lock", "rcu_read_unlock"),
+    ("rtnl_lock", "rtnl_unlock"),
+    ("lock_sock", "release_sock"),
+    ("local_irq_save", "local_irq_restore"),
+    ("local_irq_disable", "local_irq_enable"),
+    ("preempt_disable", "preempt_enable"),
+    ("pthread_mutex_lock", "pthread_mutex_unlock"),
+    ("pthread_rwlock_rdlock", "pthread_rwlock_unlock"),
+];
+
+// The name tokens opening and closing a bracket, for the functions not known, e.g., `foo_lock` and
+// `foo_unlock`. Such a function is only taken as opening a bracket closed on the path, since many
+// names have the tokens without any closing function, e.g., `spin_lock_init`
+const BRACKET_TOKENS: [(&str, &str); 7] = [
+    ("lock", "unlock"),
+    ("begin", "end"),
+    ("enter", "exit"),
+    ("acquire", "release"),
+    ("down", "up"),
+    ("disable", "enable"),
+    ("irqsave", "irqrestore"),
+];
+
+// A call opening or closing a bracket. Calls with the same key on the same argument value
+// form a bracket, e.g., `spin_lock_irqsave(&x, ...)` and `spin_unlock_irqrestore(&x, ...)`
+struct BracketCall<'a> {
+    index: usize,
+    func: &'a String,
+    key: String,
+    is_open: bool,
+    // Whether the function is a known one, rather than guessed from the name tokens
+    is_known: bool,
+    arg: Option<&'a Value>,
+}
+
+impl<'a> BracketCall<'a> {
+    fn from_instr(index: usize, instr: &'a Instr) -> Option<Self> {
+        match &instr.sem {
+            Semantics::Call { func, args } => match &**func {
+                Value::Func(f) => {
+                    let (key, is_open, is_known) = bracket_key(f)?;
+                    Some(Self {
+                        index,
+                        func: f,
+                        key,
+                        is_open,
+                        is_known,
+                        arg: args.first().map(|a| &**a),
+                    })
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn is_closed_by(&self, other: &Self) -> bool {
+        !other.is_open && self.key == other.key && self.arg == other.arg
+    }
+}
+
+// The key shared by the functions opening and closing a bracket, whether the function opens it, and
+// whether it is a known one. The key of a known function is the closing one, e.g., `mutex_unlock` for
+// `mutex_lock_interruptible`. For the others, the bracket tokens in the name are replaced with their
+// opening ones. Names without any bracket token are not brackets
+fn bracket_key(func: &str) -> Option<(String, bool, bool)> {
+    for (open, close) in BRACKET_FUNCTIONS.iter() {
+        if func == *open || func == *close {
+            return Some((close.to_string(), func == *open, true));
+        }
+    }
+    let mut is_bracket = false;
+    let mut is_open = true;
+    let tokens = func
+        .split('_')
+        .map(|token| {
+            for (open, close) in BRACKET_TOKENS.iter() {
+                if token == *open {
+                    is_bracket = true;
+                    return *open;
+                } else if token == *close {
+                    is_bracket = true;
+                    is_open = false;
+                    return *open;
+                }
+            }
+            token
+        })
+        .collect::<Vec<_>>();
+    if is_bracket {
+        Some((tokens.join("_"), is_open, false))
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BracketFeatures {
+    // Whether the target runs while a bracket is open
+    pub in_bracket: bool,
+    // The functions opening the brackets, e.g., `mutex_lock`
+    pub open_brackets: BTreeSet<String>,
+    // The functions closing them after the target, e.g., `mutex_unlock`
+    pub closers: BTreeSet<String>,
+    pub open_at_ret: bool,
+    pub unclosed_at_ret: BTreeSet<String>,
+}
+
+pub struct BracketFeatureExtractor;
+
+impl BracketFeatureExtractor {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl FeatureExtractor for BracketFeatureExtractor {
+    fn name(&self) -> String {
+        "bracket".to_string()
+    }
+
+    fn filter<'ctx>(&self, _: bool) -> bool {
+        true
+    }
+
+    fn extract(&self, _: usize, _: &Slice, trace: &Trace) -> FeatureGroup {
+        let calls = trace
+            .instrs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != trace.target)
+            .filter_map(|(i, instr)| BracketCall::from_instr(i, instr))
+            .collect::<Vec<_>>();
+
+        // The functions guessed from the name tokens open brackets only if they are closed on the path
+        let closing_keys = calls
+            .iter()
+            .filter(|call| !call.is_open)
+            .map(|call| call.key.clone())
+            .collect::<HashSet<_>>();
+        let calls = calls
+            .into_iter()
+            .filter(|call| call.is_known || !call.is_open || closing_keys.contains(&call.key))
+            .collect::<Vec<_>>();
+
+        // The brackets still open when the target runs
+        let mut open_brackets: Vec<&BracketCall> = vec![];
+        for call in calls.iter().take_while(|call| call.index < trace.target) {
+            if call.is_open {
+                open_brackets.push(call);
+            } else if let Some(i) = open_brackets.iter().rposition(|open| open.is_closed_by(call)) {
+                open_brackets.remove(i);
+            }
+        }
+
+        // Close them with the calls after the target
+        let mut closers = BTreeSet::new();
+        let mut unclosed = open_brackets.clone();
+        for call in calls.iter().skip_while(|call| call.index < trace.target) {
+            if let Some(i) = unclosed.iter().rposition(|open| open.is_closed_by(call)) {
+                closers.insert(call.func.clone());
+                unclosed.remove(i);
+            }
+        }
+
+        let unclosed_at_ret = if trace.entry_ret().is_some() {
+            unclosed.iter().map(|open| open.func.clone()).collect::<BTreeSet<_>>()
+        } else {
+            BTreeSet::new()
+        };
+
+        FeatureGroup::Bracket(BracketFeatures {
+            in_bracket: !open_brackets.is_empty(),
+            open_brackets: open_brackets.iter().map(|open| open.func.clone()).collect(),
+            closers,
+            open_at_ret: !unclosed_at_ret.is_empty(),
+            unclosed_at_ret,
+        })
+    }
+}
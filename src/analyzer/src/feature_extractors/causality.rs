@@ -1,13 +1,13 @@
-use serde::Serialize;
-use serde_json::json;
-use std::collections::HashMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 
 use crate::feature_extraction::*;
 use crate::semantics::boxed::*;
 
-#[derive(Clone, Serialize)]
-struct CausalityFeatures {
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CausalityFuncFeatures {
     // Note:
     // 1. in pre.call, this means:
     // Used by target function as argument
@@ -26,7 +26,7 @@ struct CausalityFeatures {
     pub share_argument: bool,
 }
 
-impl Default for CausalityFeatures {
+impl Default for CausalityFuncFeatures {
     fn default() -> Self {
         Self {
             used_as_arg: false,
@@ -35,6 +35,15 @@ impl Default for CausalityFeatures {
     }
 }
 
+// The related functions called before and after the target
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CausalityFeatures {
+    #[serde(rename = "pre.call")]
+    pub pre_call: BTreeMap<String, CausalityFuncFeatures>,
+    #[serde(rename = "post.call")]
+    pub post_call: BTreeMap<String, CausalityFuncFeatures>,
+}
+
 pub struct CausalityFeatureExtractor;
 
 impl CausalityFeatureExtractor {
@@ -52,29 +61,11 @@ impl FeatureExtractor for CausalityFeatureExtractor {
         true
     }
 
-    fn extract(&self, _: usize, slice: &Slice, trace: &Trace) -> serde_json::Value {
+    fn extract(&self, _: usize, slice: &Slice, trace: &Trace) -> FeatureGroup {
         let func_instrs = slice.functions.iter().map(|(_, instr)| instr).collect::<HashSet<_>>();
-        let pre_related_funcs = find_related_functions(&func_instrs, trace, TraceIterDirection::Backward);
-        let post_related_funcs = find_related_functions(&func_instrs, trace, TraceIterDirection::Forward);
-        let mut pre_map = serde_json::Map::new();
-        let mut post_map = serde_json::Map::new();
-
-        for (func, causality_features) in pre_related_funcs {
-            pre_map.insert(
-                func.clone(),
-                serde_json::to_value(causality_features).expect("Cannot turn causality_pre features into json"),
-            );
-        }
-        for (func, causality_features) in post_related_funcs {
-            post_map.insert(
-                func.clone(),
-                serde_json::to_value(causality_features).expect("Cannot turn causality_post features into json"),
-            );
-        }
-
-        json!({
-            "pre.call": serde_json::Value::Object(pre_map),
-            "post.call": serde_json::Value::Object(post_map),
+        FeatureGroup::Causality(CausalityFeatures {
+            pre_call: find_related_functions(&func_instrs, trace, TraceIterDirection::Backward),
+            post_call: find_related_functions(&func_instrs, trace, TraceIterDirection::Forward),
         })
     }
 }
@@ -83,23 +74,18 @@ fn find_related_functions(
     func_instrs: &HashSet<&String>,
     trace: &Trace,
     direction: TraceIterDirection,
-) -> HashMap<String, CausalityFeatures> {
-    let mut result = HashMap::new();
+) -> BTreeMap<String, CausalityFuncFeatures> {
+    let mut result = BTreeMap::new();
     let target_instr = &trace.instrs[trace.target];
 
     for (_, instr) in trace.iter_instrs_from_target(direction) {
         match &instr.sem {
             Semantics::Call { func, .. } => match &**func {
                 Value::Func(f) => {
-                    if f.contains("__asan")
-                        || f.contains("__sanitizer")
-                        || f.contains("__kasan")
-                        || f.contains("print")
-                        || result.contains_key(f)
-                    {
+                    if is_sanitizer_function(f) || f.contains("print") || result.contains_key(f) {
                         continue;
                     }
-                    let mut features = CausalityFeatures::default();
+                    let mut features = CausalityFuncFeatures::default();
 
                     // Check if sharing argument value.
                     features.share_argument = check_if_share_arguments(instr, target_instr);
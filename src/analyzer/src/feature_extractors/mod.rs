@@ -2,7 +2,13 @@ mod arg_pre;
 pub use arg_pre::*;
 mod arg_post;
 pub use arg_post::*;
+mod arg_bounds;
+pub use arg_bounds::*;
 mod causality;
 pub use causality::*;
 mod retval;
 pub use retval::*;
+mod resource;
+pub use resource::*;
+mod bracket;
+pub use bracket::*;
@@ -0,0 +1,235 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+
+use crate::feature_extraction::*;
+use crate::feature_extractors::retval::get_br_cond;
+use crate::semantics::boxed::*;
+
+// The `_`-separated name tokens of functions releasing an object, e.g., `kfree` and `sock_put`
+const RELEASE_FUNCTION_TOKENS: [&str; 14] = [
+    "free",
+    "kfree",
+    "vfree",
+    "kvfree",
+    "put",
+    "release",
+    "destroy",
+    "close",
+    "unref",
+    "delete",
+    "del",
+    "drop",
+    "unregister",
+    "dealloc",
+];
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ResourceObjectFeatures {
+    // Whether the tracked object comes from the target, always true for the return value
+    // e.g.
+    // 		target(..., &a, ...);
+    // 		func(a, ...);
+    pub initialized: bool,
+    // The functions consuming the object as their first argument
+    pub consumers: BTreeSet<String>,
+    // The number of calls releasing the object, e.g., `kfree(obj)`
+    pub consumed_count: usize,
+    // The object is released more than once, e.g., a double free
+    pub consumed_more_than_once: bool,
+    // Returned, stored to a non-local location, or passed as other than the first argument
+    pub escaped: bool,
+    // The object is null on the path, e.g., the acquisition failed
+    pub null_on_path: bool,
+    // The trace returns while the object is neither released nor escaped
+    pub live_at_ret: bool,
+}
+
+impl Default for ResourceObjectFeatures {
+    fn default() -> Self {
+        Self {
+            initialized: false,
+            consumers: BTreeSet::new(),
+            consumed_count: 0,
+            consumed_more_than_once: false,
+            escaped: false,
+            null_on_path: false,
+            live_at_ret: false,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ResourceFeatures {
+    // `None` if the return value is unknown
+    pub retval: Option<ResourceObjectFeatures>,
+    pub args: Vec<ResourceObjectFeatures>,
+    pub arg_num: usize,
+}
+
+pub struct ResourceFeatureExtractor;
+
+impl ResourceFeatureExtractor {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl FeatureExtractor for ResourceFeatureExtractor {
+    fn name(&self) -> String {
+        "resource".to_string()
+    }
+
+    fn filter<'ctx>(&self, _: bool) -> bool {
+        true
+    }
+
+    fn extract(&self, _: usize, _: &Slice, trace: &Trace) -> FeatureGroup {
+        let retval = match trace.target_result() {
+            Some(Value::Unknown) | Some(Value::Null) | None => None,
+            Some(value) => Some(track_object(trace, value, trace.target)),
+        };
+        let args = trace
+            .target_args()
+            .into_iter()
+            .map(|arg| match initialized_object(trace, arg) {
+                Some((i, object)) => track_object(trace, object, i),
+                None => ResourceObjectFeatures::default(),
+            })
+            .collect::<Vec<_>>();
+
+        FeatureGroup::Resource(ResourceFeatures {
+            retval,
+            arg_num: args.len(),
+            args,
+        })
+    }
+}
+
+// The object stored by the target into the location pointed by the argument, which is
+// given by the first load from the location after the target. The location is an out-param
+// only if the target wrote the object through it, rather than reading an input buffer
+fn initialized_object<'a>(trace: &'a Trace, arg: &Value) -> Option<(usize, &'a Value)> {
+    match arg {
+        Value::Alloc(_) | Value::GEP { .. } | Value::Sym(_) | Value::Arg(_) | Value::Glob(_) | Value::GlobSym(_) => {}
+        _ => return None,
+    }
+    for (i, instr) in trace.iter_instrs_from_target(TraceIterDirection::Forward) {
+        if let Semantics::Load { loc } = &instr.sem {
+            if &**loc == arg {
+                return match &instr.res {
+                    Some(Value::Unknown) | Some(Value::Null) | None => None,
+                    Some(res) if written_by_target(trace, arg, res) => Some((i, res)),
+                    Some(_) => None,
+                };
+            }
+        }
+    }
+    None
+}
+
+// Whether the value in the location is produced by the target, which is never stepped in: a fresh value
+// unknown before the target, where the location held no object
+fn written_by_target(trace: &Trace, loc: &Value, value: &Value) -> bool {
+    let is_null = |v: &Value| v == &Value::Null || v == &Value::Int(0);
+    trace
+        .iter_instrs_from_target(TraceIterDirection::Backward)
+        .into_iter()
+        .all(|(_, instr)| {
+            let stored_object = match &instr.sem {
+                Semantics::Store { loc: l, val } => &**l == loc && !is_null(val),
+                _ => false,
+            };
+            let known = instr.res.as_ref() == Some(value) || instr.sem.operands().iter().any(|(_, op)| &***op == value);
+            !stored_object && !known
+        })
+}
+
+// Track the object forward from the instruction producing it
+fn track_object(trace: &Trace, object: &Value, from: usize) -> ResourceObjectFeatures {
+    let mut features = ResourceObjectFeatures::default();
+    features.initialized = true;
+
+    // Pointers derived from the object, e.g., `&obj->field`
+    let mut child_ptrs: HashSet<Value> = HashSet::new();
+    let mut null_check = None;
+
+    for (_, instr) in trace.iter_instrs_from(TraceIterDirection::Forward, from) {
+        match &instr.sem {
+            Semantics::Call { func, args } => {
+                if let Value::Func(f) = &**func {
+                    if is_ignored_function(f) {
+                        continue;
+                    }
+                }
+                for (i, arg) in args.iter().enumerate() {
+                    if &**arg == object {
+                        match (i, &**func) {
+                            (0, Value::Func(f)) => {
+                                features.consumers.insert(f.clone());
+                                if is_release_function(f) {
+                                    features.consumed_count += 1;
+                                }
+                            }
+                            _ => features.escaped = true,
+                        }
+                    } else if child_ptrs.contains(&**arg) {
+                        // E.g., the object is linked into a list by `list_add(&obj->list, head)`
+                        features.escaped = true;
+                    }
+                }
+            }
+            Semantics::Store { loc, val } => {
+                if &**val == object || child_ptrs.contains(&**val) {
+                    match &**loc {
+                        Value::Alloc(_) => {}
+                        _ => features.escaped = true,
+                    }
+                }
+            }
+            Semantics::GEP { loc, .. } => {
+                if &**loc == object || child_ptrs.contains(&**loc) {
+                    child_ptrs.insert(instr.res.clone().unwrap());
+                }
+            }
+            Semantics::ICmp { op0, op1, .. } => {
+                let is_null = |v: &Value| v == &Value::Null || v == &Value::Int(0);
+                if (&**op0 == object && is_null(op1)) || (&**op1 == object && is_null(op0)) {
+                    null_check = instr.res.clone();
+                }
+            }
+            Semantics::CondBr { cond, br } => {
+                if let Some(Value::ICmp { pred, .. }) = &null_check {
+                    if Some(&**cond) == null_check.as_ref() && get_br_cond(*pred, br) == "eq" {
+                        features.null_on_path = true;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(Instr {
+        sem: Semantics::Ret { op },
+        ..
+    }) = trace.entry_ret()
+    {
+        if let Some(op) = op {
+            if &**op == object || op.contains(object) || child_ptrs.contains(&**op) {
+                features.escaped = true;
+            }
+        }
+        features.live_at_ret = features.consumed_count == 0 && !features.escaped && !features.null_on_path;
+    }
+    features.consumed_more_than_once = features.consumed_count > 1;
+    features
+}
+
+fn is_release_function(f: &str) -> bool {
+    let f = f.to_lowercase();
+    f.split('_').any(|token| RELEASE_FUNCTION_TOKENS.contains(&token))
+}
+
+fn is_ignored_function(f: &str) -> bool {
+    is_sanitizer_function(f) || f.contains("print")
+}
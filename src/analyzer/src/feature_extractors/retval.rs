@@ -1,15 +1,85 @@
-use serde_json::json;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
 use crate::feature_extraction::*;
 use crate::semantics::boxed::*;
 use crate::semantics::*;
 
-pub struct ReturnValueFeatureExtractor;
+// The `_`-separated name tokens of functions reporting errors, e.g., `dev_err` and `pr_warn`
+const ERROR_FUNCTION_TOKENS: [&str; 10] = [
+    "err", "error", "warn", "printk", "log", "panic", "bug", "abort", "fail", "fatal",
+];
+
+// The largest error number encoded in an error pointer, as in `include/linux/err.h`
+const MAX_ERRNO: i64 = 4095;
+
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BranchOutcomeKind {
+    RetNegative,
+    RetConst,
+    ErrorCall,
+    Continue,
+    Unreachable,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct BranchOutcome {
+    // `None` if not checked
+    pub kind: Option<BranchOutcomeKind>,
+    pub ret_value: Option<i64>,
+    pub error_call: bool,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckKind {
+    Null,
+    Const,
+    NonConst,
+    ErrPtr,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReturnValueCheck {
+    pub checked: bool,
+    // `None` if not checked or the check is not recognized
+    pub check_kind: Option<CheckKind>,
+    pub indir_checked: bool,
+    pub check_cond: String,
+    pub compared_with_const: i64,
+    pub compared_with_non_const: bool,
+    pub checked_consts: Vec<i64>,
+    pub outcome: BranchOutcome,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReturnValueContext {
+    pub used_in_call: bool,
+    pub used_in_bin: bool,
+    pub stored_not_local: bool,
+    pub derefed_read: bool,
+    pub derefed_write: bool,
+    pub returned: bool,
+    pub indir_returned: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReturnValueFeatures {
+    pub check: ReturnValueCheck,
+    pub ctx: ReturnValueContext,
+}
+
+pub struct ReturnValueFeatureExtractor {
+    checker_functions: HashSet<String>,
+}
 
 impl ReturnValueFeatureExtractor {
-    pub fn new() -> Self {
-        Self
+    pub fn new(checker_functions: Vec<String>) -> Self {
+        Self {
+            checker_functions: checker_functions.into_iter().collect(),
+        }
     }
 }
 
@@ -23,13 +93,15 @@ impl FeatureExtractor for ReturnValueFeatureExtractor {
         has_return_type
     }
 
-    fn extract(&self, _: usize, _: &Slice, trace: &Trace) -> serde_json::Value {
+    fn extract(&self, _: usize, _: &Slice, trace: &Trace) -> FeatureGroup {
         // For the check of return value
         let mut checked = false;
         let mut indir_checked = false;
         let mut br_cond = "".to_string();
         let mut compared_with_const = 0;
         let mut compared_with_non_const = false;
+        let mut checked_consts = vec![];
+        let mut check_kind = None;
 
         // For the context of return value
         let mut used_in_call = false;
@@ -44,6 +116,10 @@ impl FeatureExtractor for ReturnValueFeatureExtractor {
         let mut child_ptrs: HashSet<Value> = HashSet::new();
         let mut tracked_values: HashSet<Value> = HashSet::new();
         let mut icmp = None;
+        // The value which is true iff the return value is (or is not) an error pointer
+        let mut err_cond: Option<(Value, bool)> = None;
+        // The index of the branch taken on the check
+        let mut checked_branch = None;
         let mut had_used = 0;
 
         // Maybe `None` value?
@@ -51,19 +127,32 @@ impl FeatureExtractor for ReturnValueFeatureExtractor {
         if let Some(value) = trace.target_result().clone() {
             retval = value;
         } else {
-            return json!({});
+            return FeatureGroup::ReturnValue(None);
         }
 
         // Start iterating from the target node forward
-        for (_, instr) in trace.iter_instrs_from_target(TraceIterDirection::Forward) {
+        for (i, instr) in trace.iter_instrs_from_target(TraceIterDirection::Forward) {
             match &instr.sem {
-                Semantics::ICmp { op0, op1, .. } => {
+                Semantics::ICmp { pred, op0, op1 } => {
+                    if let Some((value, is_err)) = &err_cond {
+                        // E.g., `IS_ERR(p) != 0`
+                        if let Some(is_err) = cmp_with_zero(*pred, op0, op1, value, *is_err) {
+                            err_cond = Some((instr.res.clone().unwrap(), is_err));
+                            continue;
+                        }
+                    }
                     if had_used <= 1 && !derefed_write && !derefed_read {
                         let retval_is_op0 = **op0 == retval;
                         let retval_is_op1 = **op1 == retval;
                         if checked == false && (retval_is_op0 || retval_is_op1) {
                             checked = true;
-                            icmp = Some(instr.res.clone().unwrap());
+                            match err_ptr_check(*pred, op0, op1, &retval) {
+                                Some(is_err) => {
+                                    check_kind = Some(CheckKind::ErrPtr);
+                                    err_cond = Some((instr.res.clone().unwrap(), is_err));
+                                }
+                                None => icmp = Some(instr.res.clone().unwrap()),
+                            }
                         } else if tracked_values.contains(&**op0)
                             || tracked_values.contains(&**op1)
                             || child_ptrs.contains(&**op0)
@@ -74,6 +163,12 @@ impl FeatureExtractor for ReturnValueFeatureExtractor {
                     }
                 }
                 Semantics::CondBr { cond, br } => {
+                    if let Some((value, is_err)) = &err_cond {
+                        if &**cond == value {
+                            br_cond = if br.is_then() == *is_err { "err" } else { "not_err" }.to_string();
+                            checked_branch.get_or_insert(i);
+                        }
+                    }
                     if let Some(icmp) = &icmp {
                         if &**cond == icmp {
                             if let Some((pred, op0, op1)) = icmp_pred_op0_op1(icmp) {
@@ -81,16 +176,52 @@ impl FeatureExtractor for ReturnValueFeatureExtractor {
                                 let op1_num = num_of_value(&op1);
                                 if let Some(num) = op0_num.or(op1_num) {
                                     compared_with_const = num;
+                                    checked_consts = vec![num];
+                                    check_kind = if op0 == Value::Null || op1 == Value::Null {
+                                        Some(CheckKind::Null)
+                                    } else {
+                                        Some(CheckKind::Const)
+                                    };
                                 } else {
                                     compared_with_non_const = true;
+                                    check_kind = Some(CheckKind::NonConst);
                                 }
                                 br_cond = get_br_cond(pred, br);
+                                checked_branch.get_or_insert(i);
                             }
                         }
                     }
                 }
-                Semantics::Call { args, .. } => {
-                    if args
+                Semantics::Switch {
+                    cond,
+                    cases,
+                    taken_cases,
+                } => {
+                    if had_used <= 1 && !derefed_write && !derefed_read {
+                        if checked == false && **cond == retval {
+                            checked = true;
+                            check_kind = Some(CheckKind::Const);
+                            let (num, cond, consts) = get_switch_cond(cases, taken_cases);
+                            compared_with_const = num;
+                            checked_consts = consts;
+                            br_cond = cond;
+                            checked_branch.get_or_insert(i);
+                        } else if tracked_values.contains(&**cond) || child_ptrs.contains(&**cond) {
+                            indir_checked = true;
+                        }
+                    }
+                }
+                Semantics::Call { func, args } => {
+                    let is_checker = match &**func {
+                        Value::Func(f) => self.checker_functions.contains(f),
+                        _ => false,
+                    };
+                    if is_checker && checked == false && args.iter().find(|a| &***a == &retval).is_some() {
+                        // E.g., `IS_ERR(p)`, which is a check rather than a use of the return value
+                        checked = true;
+                        check_kind = Some(CheckKind::ErrPtr);
+                        err_cond = instr.res.clone().map(|res| (res, true));
+                    } else if args
                         .iter()
                         .find(|a| &***a == &retval || child_ptrs.contains(&**a))
                         .is_some()
@@ -149,30 +280,92 @@ impl FeatureExtractor for ReturnValueFeatureExtractor {
                         child_ptrs.insert(instr.res.clone().unwrap());
                     }
                 }
+                Semantics::Select { true_val, false_val, .. } => {
+                    if **true_val == retval
+                        || **false_val == retval
+                        || child_ptrs.contains(&**true_val)
+                        || child_ptrs.contains(&**false_val)
+                    {
+                        // The selected value may still be the return value
+                        child_ptrs.insert(instr.res.clone().unwrap());
+                    }
+                }
                 _ => {}
             }
         }
 
-        json!({
-            "check": {
-                "checked": checked,
-                "indir_checked": indir_checked,
-                "check_cond": br_cond,
-                "compared_with_const": compared_with_const,
-                "compared_with_non_const": compared_with_non_const,
+        FeatureGroup::ReturnValue(Some(ReturnValueFeatures {
+            check: ReturnValueCheck {
+                checked,
+                check_kind,
+                indir_checked,
+                check_cond: br_cond,
+                compared_with_const,
+                compared_with_non_const,
+                checked_consts,
+                outcome: checked_branch.map_or_else(BranchOutcome::default, |i| branch_outcome(trace, i)),
             },
-            "ctx": {
-                "used_in_call": used_in_call,
-                "used_in_bin": used_in_bin,
-                // "stored": stored,
-                "stored_not_local": stored_not_local,
-                "derefed_read": derefed_read,
-                "derefed_write": derefed_write,
-                "returned": returned,
-                "indir_returned": indir_returned,
+            ctx: ReturnValueContext {
+                used_in_call,
+                used_in_bin,
+                stored_not_local,
+                derefed_read,
+                derefed_write,
+                returned,
+                indir_returned,
             },
-        })
+        }))
+    }
+}
+
+// The outcome of the branch taken at the given node: reaching `Ret` of the function with the branch
+// with a negative or other constant value, calling an error function, or continuing normally. The
+// returns of the callees stepped in are skipped. Traces ending without `Ret`, e.g., at an
+// `unreachable`, are regarded as unreachable
+fn branch_outcome(trace: &Trace, from: usize) -> BranchOutcome {
+    let mut error_call = false;
+    let depth = trace.instrs[from].depth;
+    for (_, instr) in trace.iter_instrs_from(TraceIterDirection::Forward, from) {
+        match &instr.sem {
+            Semantics::Call { func, .. } => {
+                if let Value::Func(f) = &**func {
+                    error_call |= is_error_function(f);
+                }
+            }
+            Semantics::Ret { op } if instr.depth <= depth => {
+                let ret_value = op.as_ref().and_then(|op| num_of_value(op));
+                let kind = match ret_value {
+                    Some(num) if num < 0 => BranchOutcomeKind::RetNegative,
+                    _ if error_call => BranchOutcomeKind::ErrorCall,
+                    Some(_) => BranchOutcomeKind::RetConst,
+                    None => BranchOutcomeKind::Continue,
+                };
+                return BranchOutcome {
+                    kind: Some(kind),
+                    ret_value,
+                    error_call,
+                };
+            }
+            _ => {}
+        }
+    }
+    BranchOutcome {
+        kind: Some(if error_call {
+            BranchOutcomeKind::ErrorCall
+        } else {
+            BranchOutcomeKind::Unreachable
+        }),
+        ret_value: None,
+        error_call,
+    }
+}
+
+fn is_error_function(f: &str) -> bool {
+    if is_sanitizer_function(f) {
+        return false;
     }
+    let f = f.to_lowercase();
+    f.split('_').any(|token| ERROR_FUNCTION_TOKENS.contains(&token))
 }
 
 pub fn num_of_value(v: &Value) -> Option<i64> {
@@ -207,6 +400,56 @@ pub fn get_br_cond(pred: Predicate, br: &Branch) -> String {
     br_cond.to_string()
 }
 
+// The check condition of the taken switch case together with the constants it compares with,
+// i.e., `eq` or `in` the case values going to the taken destination, or `not_in` all the case
+// values for the default destination. The first constant is the compared one.
+pub fn get_switch_cond(cases: &[i64], taken_cases: &[i64]) -> (i64, String, Vec<i64>) {
+    let (cond, consts) = match taken_cases.len() {
+        0 => ("not_in", cases.to_vec()),
+        1 => ("eq", taken_cases.to_vec()),
+        _ => ("in", taken_cases.to_vec()),
+    };
+    (consts.first().cloned().unwrap_or(0), cond.to_string(), consts)
+}
+
+// The inline form of `IS_ERR_VALUE(p)`, i.e., `(unsigned long)p >= (unsigned long)-MAX_ERRNO`.
+// Returns whether the comparison holds for error pointers
+fn err_ptr_check(pred: Predicate, op0: &Value, op1: &Value, retval: &Value) -> Option<bool> {
+    let is_err_bound = |v: &Value| match v {
+        Value::Int(i) => *i < 0 && *i >= -MAX_ERRNO - 1,
+        _ => false,
+    };
+    if op0 == retval && is_err_bound(op1) {
+        match pred {
+            Predicate::UGE | Predicate::UGT => Some(true),
+            Predicate::ULE | Predicate::ULT => Some(false),
+            _ => None,
+        }
+    } else if op1 == retval && is_err_bound(op0) {
+        match pred {
+            Predicate::ULE | Predicate::ULT => Some(true),
+            Predicate::UGE | Predicate::UGT => Some(false),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+// Whether the comparison of the value with zero holds iff the value holds
+fn cmp_with_zero(pred: Predicate, op0: &Value, op1: &Value, value: &Value, holds: bool) -> Option<bool> {
+    let is_zero = |v: &Value| v == &Value::Int(0);
+    if (op0 == value && is_zero(op1)) || (op1 == value && is_zero(op0)) {
+        match pred {
+            Predicate::NE => Some(holds),
+            Predicate::EQ => Some(!holds),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
 fn icmp_pred_op0_op1(v: &Value) -> Option<(Predicate, Value, Value)> {
     match v {
         Value::ICmp { pred, op0, op1 } => Some((pred.clone(), *op0.clone(), *op1.clone())),
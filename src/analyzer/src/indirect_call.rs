@@ -0,0 +1,266 @@
+use llir::{types::*, values::*, *};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use crate::call_graph::CallEdgeKind;
+use crate::utils::constant_at_location;
+
+// How to resolve the callees of calls through function pointers
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IndirectCallResolution {
+    // Drop indirect calls
+    None,
+    // Address-taken functions whose type matches the type of the call
+    TypeSignature,
+    // Functions whose addresses are stored into the global or struct field the callee is loaded from
+    Precise,
+}
+
+impl FromStr for IndirectCallResolution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "type" => Ok(Self::TypeSignature),
+            "precise" => Ok(Self::Precise),
+            _ => Err(format!("Unknown indirect call resolution `{}`", s)),
+        }
+    }
+}
+
+// The memory slot holding function pointers: either a global variable (or an array of function
+// pointers), or a field of a struct type, e.g., `ops->open` is field 0 of `struct.file_operations`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum FunctionSlot<'ctx> {
+    Global(GlobalVariable<'ctx>),
+    Field(StructType<'ctx>, usize),
+}
+
+pub struct IndirectCallResolver<'ctx> {
+    resolution: IndirectCallResolution,
+    address_taken: Vec<Function<'ctx>>,
+    slots: HashMap<FunctionSlot<'ctx>, HashSet<Function<'ctx>>>,
+}
+
+impl<'ctx> IndirectCallResolver<'ctx> {
+    pub fn new(module: &Module<'ctx>, resolution: IndirectCallResolution) -> Self {
+        let mut address_taken = HashSet::new();
+        let mut slots = HashMap::new();
+        if resolution != IndirectCallResolution::None {
+            // Functions stored in the initializers of globals
+            for global in module.iter_global_variables() {
+                if let Some(init) = global.initializer() {
+                    collect_functions(&init, &mut address_taken);
+                    collect_initializer_slots(&init, Some(global), &mut slots);
+                }
+            }
+            // Functions used as values in instructions, and the slots they are stored to
+            for func in module.iter_functions() {
+                for instr in func.iter_instructions() {
+                    let operands = match CallBaseInstruction::from_instruction(instr) {
+                        Some(call_instr) => call_instr.arguments(),
+                        None => instr.iter_operands().collect(),
+                    };
+                    for operand in operands {
+                        if let Operand::Constant(c) = operand {
+                            collect_functions(&c, &mut address_taken);
+                        }
+                    }
+                    if let Instruction::Store(store_instr) = instr {
+                        if let Some(f) = function_of_operand(&store_instr.value()) {
+                            if let Some(slot) = slot_of_location(&store_instr.location()) {
+                                slots.entry(slot).or_insert_with(HashSet::new).insert(f);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let mut address_taken = address_taken.into_iter().collect::<Vec<_>>();
+        address_taken.sort_by_key(|f| f.name());
+        Self {
+            resolution,
+            address_taken,
+            slots,
+        }
+    }
+
+    // Resolve the possible callees of an indirect call, with the kind of resolution
+    pub fn resolve(&self, call_instr: &CallBaseInstruction<'ctx>) -> Vec<(Function<'ctx>, CallEdgeKind)> {
+        if call_instr.callee_function().is_some() || call_instr.is_inline_asm_call() {
+            return vec![];
+        }
+        match self.resolution {
+            IndirectCallResolution::None => vec![],
+            IndirectCallResolution::TypeSignature => {
+                let func_type = call_instr.callee_function_type();
+                self.address_taken
+                    .iter()
+                    .filter(|f| f.get_function_type() == func_type)
+                    .map(|f| (*f, CallEdgeKind::TypeSignature))
+                    .collect()
+            }
+            IndirectCallResolution::Precise => {
+                let location = match strip_casts(call_instr.callee()) {
+                    Operand::Instruction(Instruction::Load(load_instr)) => Some(load_instr.location()),
+                    _ => None,
+                };
+                // The exact callee if it is loaded from a constant function pointer table
+                let constant_callee = location
+                    .and_then(constant_at_location)
+                    .and_then(|c| function_of_operand(&c.as_operand()));
+                if let Some(f) = constant_callee {
+                    if arguments_compatible(&f, call_instr) {
+                        return vec![(f, CallEdgeKind::Precise)];
+                    }
+                }
+                let slot = location.and_then(|location| slot_of_location(&location));
+                let mut callees = match slot.and_then(|slot| self.slots.get(&slot)) {
+                    Some(funcs) => funcs
+                        .iter()
+                        .filter(|f| arguments_compatible(f, call_instr))
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                    None => vec![],
+                };
+                callees.sort_by_key(|f| f.name());
+                callees.into_iter().map(|f| (f, CallEdgeKind::Precise)).collect()
+            }
+        }
+    }
+}
+
+fn arguments_compatible<'ctx>(f: &Function<'ctx>, call_instr: &CallBaseInstruction<'ctx>) -> bool {
+    if f.is_var_arg() {
+        f.num_arguments() <= call_instr.num_arguments()
+    } else {
+        f.num_arguments() == call_instr.num_arguments()
+    }
+}
+
+// Look through the casts, e.g., `bitcast (void (i8*)* @f to void (%struct.S*)*)`
+fn strip_casts<'ctx>(operand: Operand<'ctx>) -> Operand<'ctx> {
+    match operand {
+        Operand::Instruction(Instruction::Unary(unary_instr)) => strip_casts(unary_instr.op0()),
+        Operand::Constant(Constant::ConstExpr(ConstExpr::Unary(unary_expr))) => {
+            strip_casts(unary_expr.op0().as_operand())
+        }
+        _ => operand,
+    }
+}
+
+fn function_of_operand<'ctx>(operand: &Operand<'ctx>) -> Option<Function<'ctx>> {
+    match strip_casts(*operand) {
+        Operand::Constant(Constant::Function(f)) => Some(f),
+        _ => None,
+    }
+}
+
+// Collect all the functions referenced in a constant
+fn collect_functions<'ctx>(constant: &Constant<'ctx>, funcs: &mut HashSet<Function<'ctx>>) {
+    match constant {
+        Constant::Function(f) => {
+            funcs.insert(*f);
+        }
+        Constant::Struct(s) => s.elements().iter().for_each(|c| collect_functions(c, funcs)),
+        Constant::Array(a) => a.elements().iter().for_each(|c| collect_functions(c, funcs)),
+        Constant::Vector(v) => v.elements().iter().for_each(|c| collect_functions(c, funcs)),
+        Constant::ConstExpr(e) => {
+            for i in 0..e.num_operands() {
+                if let Some(c) = e.operand(i) {
+                    collect_functions(&c, funcs);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// Record the functions in the initializer of a global into the slots; `global` is set only for
+// the top level (or the top level array), whose elements are loaded through the global itself
+fn collect_initializer_slots<'ctx>(
+    constant: &Constant<'ctx>,
+    global: Option<GlobalVariable<'ctx>>,
+    slots: &mut HashMap<FunctionSlot<'ctx>, HashSet<Function<'ctx>>>,
+) {
+    match constant {
+        Constant::Struct(s) => {
+            let struct_type = s.get_struct_type();
+            for (i, elem) in s.elements().iter().enumerate() {
+                match function_of_operand(&elem.as_operand()) {
+                    Some(f) => {
+                        let slot = FunctionSlot::Field(struct_type, i);
+                        slots.entry(slot).or_insert_with(HashSet::new).insert(f);
+                    }
+                    None => collect_initializer_slots(elem, None, slots),
+                }
+            }
+        }
+        Constant::Array(a) => {
+            for elem in a.elements() {
+                match (function_of_operand(&elem.as_operand()), global) {
+                    (Some(f), Some(g)) => {
+                        slots
+                            .entry(FunctionSlot::Global(g))
+                            .or_insert_with(HashSet::new)
+                            .insert(f);
+                    }
+                    (Some(_), None) => {}
+                    (None, _) => collect_initializer_slots(&elem, global, slots),
+                }
+            }
+        }
+        _ => {
+            if let (Some(f), Some(g)) = (function_of_operand(&constant.as_operand()), global) {
+                slots
+                    .entry(FunctionSlot::Global(g))
+                    .or_insert_with(HashSet::new)
+                    .insert(f);
+            }
+        }
+    }
+}
+
+// Get the slot of a location which holds a function pointer
+fn slot_of_location<'ctx>(location: &Operand<'ctx>) -> Option<FunctionSlot<'ctx>> {
+    match strip_casts(*location) {
+        Operand::Constant(Constant::Global(Global::Variable(g))) => Some(FunctionSlot::Global(g)),
+        Operand::Instruction(Instruction::GetElementPtr(gep)) => slot_of_gep(gep.location(), gep.indices()),
+        Operand::Constant(Constant::ConstExpr(ConstExpr::GetElementPtr(gep))) => slot_of_gep(
+            gep.location().as_operand(),
+            gep.indices().iter().map(|c| c.as_operand()).collect(),
+        ),
+        _ => None,
+    }
+}
+
+// The innermost struct field addressed by the gep; the global itself if no struct is involved
+fn slot_of_gep<'ctx>(base: Operand<'ctx>, indices: Vec<Operand<'ctx>>) -> Option<FunctionSlot<'ctx>> {
+    let mut curr_type = match base.get_type() {
+        Type::Pointer(p) => p.element_type(),
+        _ => return None,
+    };
+    let mut field = None;
+    for index in indices.iter().skip(1) {
+        match curr_type {
+            Type::Struct(s) => {
+                let i = match index {
+                    Operand::Constant(Constant::Int(i)) => i.zext_value() as usize,
+                    _ => return None,
+                };
+                field = Some(FunctionSlot::Field(s, i));
+                curr_type = s.element_type(i)?;
+            }
+            Type::Array(a) => curr_type = a.element_type(),
+            _ => break,
+        }
+    }
+    match field {
+        Some(field) => Some(field),
+        None => match strip_casts(base) {
+            Operand::Constant(Constant::Global(Global::Variable(g))) => Some(FunctionSlot::Global(g)),
+            _ => None,
+        },
+    }
+}
@@ -1,10 +1,14 @@
 pub extern crate llir;
 
 pub mod call_graph;
+pub mod detect;
 pub mod feature_extraction;
 pub mod feature_extractors;
+pub mod indirect_call;
 pub mod options;
 pub mod semantics;
 pub mod slicer;
+pub mod spec_inference;
+pub mod storage;
 pub mod symbolic_execution;
 pub mod utils;
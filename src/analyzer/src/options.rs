@@ -14,6 +14,17 @@ pub trait IOOptions {
 
     fn basename_of_bc_file(&self) -> Option<&str>;
 
+    // The SQLite database given by `--database`, if any
+    fn database(&self) -> Option<&str> {
+        None
+    }
+
+    // Store the results in a single SQLite database instead of the directory layout below. A relative
+    // database path is resolved against the output directory, so every binary opens the same file
+    fn database_path(&self) -> Option<PathBuf> {
+        self.database().map(|database| self.output_path().join(database))
+    }
+
     fn with_name_of_bc_file(&self, path: PathBuf) -> PathBuf {
         match self.basename_of_bc_file() {
             Some(package) => path.join(package),
@@ -76,6 +87,23 @@ pub trait IOOptions {
             .join(format!("{}.json", trace_id))
     }
 
+    // Options related with completion records of slices
+    fn completion_dir(&self) -> PathBuf {
+        self.output_path().join("completions")
+    }
+
+    fn completion_target_slice_file_path(&self, target: &str, slice_id: usize) -> PathBuf {
+        self.with_name_of_bc_file(self.completion_dir().join(target))
+            .join(format!("{}.json", slice_id))
+    }
+
+    fn completion_target_package_slice_file_path(&self, target: &str, package: &str, slice_id: usize) -> PathBuf {
+        self.completion_dir()
+            .join(target)
+            .join(package)
+            .join(format!("{}.json", slice_id))
+    }
+
     // Options related with feature
     fn feature_dir(&self) -> PathBuf {
         self.output_path().join("features")
@@ -67,6 +67,16 @@ pub enum PredicateDef {
     ULT,
 }
 
+// The default bit width for the comparisons whose width is not recorded, e.g., pointers
+pub const DEFAULT_INT_WIDTH: u32 = 64;
+
+// How a cast extends an integer to a wider one
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Extension {
+    Sign,
+    Zero,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Branch {
     Then,
@@ -125,6 +135,11 @@ macro_rules! decl_value_with_wrapper {
                 func: $wrapper<Value>,
                 args: Vec<$wrapper<Value>>,
             },
+            Select {
+                cond: $wrapper<Value>,
+                true_val: $wrapper<Value>,
+                false_val: $wrapper<Value>,
+            },
             Unknown,
         }
 
@@ -175,10 +190,28 @@ macro_rules! decl_value_with_wrapper {
                             _ => None,
                         }
                     },
+                    Value::Select { cond, true_val, false_val } => {
+                        match cond.eval_constant_cond() {
+                            Some(true) => true_val.eval_constant_value(),
+                            Some(false) => false_val.eval_constant_value(),
+                            None => match (true_val.eval_constant_value(), false_val.eval_constant_value()) {
+                                (Some(t), Some(f)) if t == f => Some(t),
+                                _ => None,
+                            },
+                        }
+                    },
                     _ => None,
                 }
             }
 
+            // Evaluate the value as a boolean condition if it can be decided statically
+            pub fn eval_constant_cond(&self) -> Option<bool> {
+                match self.as_comparison() {
+                    Some(comparison) => comparison.eval_constant_cond(),
+                    None => self.eval_constant_value().map(|i| i != 0),
+                }
+            }
+
             pub fn into_z3_ast<'ctx>(
                 &self,
                 symbol_map: &mut HashMap<Value, z3::Symbol>,
@@ -205,6 +238,16 @@ macro_rules! decl_value_with_wrapper {
                             _ => None,
                         }
                     }
+                    Value::Select { cond, true_val, false_val } => {
+                        match (
+                            cond.into_z3_bool(symbol_map, symbol_id, z3_ctx),
+                            true_val.into_z3_ast(symbol_map, symbol_id, z3_ctx),
+                            false_val.into_z3_ast(symbol_map, symbol_id, z3_ctx),
+                        ) {
+                            (Some(cond), Some(true_val), Some(false_val)) => Some(cond.ite(&true_val, &false_val)),
+                            _ => None,
+                        }
+                    }
                     Value::Unknown => None,
                     Value::Sym(id) | Value::ConstSym(id) | Value::GlobSym(id) => {
                         let symbol = symbol_map.entry(self.clone()).or_insert_with(|| {
@@ -223,6 +266,146 @@ macro_rules! decl_value_with_wrapper {
                     }
                 }
             }
+
+            // Encode the value as a z3 boolean, e.g., the condition of a select
+            pub fn into_z3_bool<'ctx>(
+                &self,
+                symbol_map: &mut HashMap<Value, z3::Symbol>,
+                symbol_id: &mut u32,
+                z3_ctx: &'ctx z3::Context,
+            ) -> Option<z3::ast::Bool<'ctx>> {
+                use z3::ast::Ast;
+                match self.as_comparison() {
+                    Some(comparison) => comparison.into_z3_ast(symbol_map, symbol_id, z3_ctx),
+                    None => self
+                        .into_z3_ast(symbol_map, symbol_id, z3_ctx)
+                        .map(|v| v._eq(&z3::ast::Int::from_i64(z3_ctx, 0)).not()),
+                }
+            }
+
+            // Encode the value as a bit-vector of `width` bits. A value with a recorded width is encoded
+            // at that width, and then truncated or extended to `width` as by the casts in `extensions`,
+            // which are the ones feeding the enclosing comparison. A value not cast there is zero-extended
+            pub fn into_z3_bv<'ctx>(
+                &self,
+                width: u32,
+                int_widths: &IntWidths,
+                extensions: &HashMap<Value, Extension>,
+                symbol_map: &mut HashMap<Value, z3::Symbol>,
+                symbol_id: &mut u32,
+                z3_ctx: &'ctx z3::Context,
+            ) -> Option<z3::ast::BV<'ctx>> {
+                match int_widths.value_width(self) {
+                    Some(value_width) if value_width > width => self
+                        .into_z3_bv_of_width(value_width, int_widths, extensions, symbol_map, symbol_id, z3_ctx)
+                        .map(|bv| bv.extract(width - 1, 0)),
+                    Some(value_width) if value_width < width => self
+                        .into_z3_bv_of_width(value_width, int_widths, extensions, symbol_map, symbol_id, z3_ctx)
+                        .map(|bv| {
+                            if extensions.get(self) == Some(&Extension::Sign) {
+                                bv.sign_ext(width - value_width)
+                            } else {
+                                bv.zero_ext(width - value_width)
+                            }
+                        }),
+                    _ => self.into_z3_bv_of_width(width, int_widths, extensions, symbol_map, symbol_id, z3_ctx),
+                }
+            }
+
+            // Encode the value at exactly `width` bits, where the operands are encoded at their own widths
+            // and cast to `width`. The width of nested comparisons (e.g., the condition of a select) is
+            // looked up from `int_widths`.
+            fn into_z3_bv_of_width<'ctx>(
+                &self,
+                width: u32,
+                int_widths: &IntWidths,
+                extensions: &HashMap<Value, Extension>,
+                symbol_map: &mut HashMap<Value, z3::Symbol>,
+                symbol_id: &mut u32,
+                z3_ctx: &'ctx z3::Context,
+            ) -> Option<z3::ast::BV<'ctx>> {
+                use z3::*;
+                match self {
+                    Value::Int(i) => Some(ast::BV::from_i64(z3_ctx, *i, width)),
+                    Value::Null => Some(ast::BV::from_i64(z3_ctx, 0, width)),
+                    Value::Bin { op, op0, op1 } => {
+                        match (
+                            op0.into_z3_bv(width, int_widths, extensions, symbol_map, symbol_id, z3_ctx),
+                            op1.into_z3_bv(width, int_widths, extensions, symbol_map, symbol_id, z3_ctx),
+                        ) {
+                            (Some(op0), Some(op1)) => match op {
+                                BinOp::Add => Some(op0.bvadd(&op1)),
+                                BinOp::Sub => Some(op0.bvsub(&op1)),
+                                BinOp::Mul => Some(op0.bvmul(&op1)),
+                                BinOp::UDiv => Some(op0.bvudiv(&op1)),
+                                BinOp::SDiv => Some(op0.bvsdiv(&op1)),
+                                BinOp::URem => Some(op0.bvurem(&op1)),
+                                BinOp::SRem => Some(op0.bvsrem(&op1)),
+                                BinOp::Shl => Some(op0.bvshl(&op1)),
+                                BinOp::LShr => Some(op0.bvlshr(&op1)),
+                                BinOp::AShr => Some(op0.bvashr(&op1)),
+                                BinOp::And => Some(op0.bvand(&op1)),
+                                BinOp::Or => Some(op0.bvor(&op1)),
+                                BinOp::Xor => Some(op0.bvxor(&op1)),
+                                _ => None,
+                            },
+                            _ => None,
+                        }
+                    }
+                    Value::Select { cond, true_val, false_val } => {
+                        match (
+                            cond.into_z3_bv_bool(int_widths, symbol_map, symbol_id, z3_ctx),
+                            true_val.into_z3_bv(width, int_widths, extensions, symbol_map, symbol_id, z3_ctx),
+                            false_val.into_z3_bv(width, int_widths, extensions, symbol_map, symbol_id, z3_ctx),
+                        ) {
+                            (Some(cond), Some(true_val), Some(false_val)) => Some(cond.ite(&true_val, &false_val)),
+                            _ => None,
+                        }
+                    }
+                    // A comparison used as an integer, e.g., `zext i1 %cmp`
+                    Value::ICmp { .. } => self
+                        .into_z3_bv_bool(int_widths, symbol_map, symbol_id, z3_ctx)
+                        .map(|cond| {
+                            cond.ite(
+                                &ast::BV::from_i64(z3_ctx, 1, width),
+                                &ast::BV::from_i64(z3_ctx, 0, width),
+                            )
+                        }),
+                    Value::Unknown => None,
+                    Value::Sym(id) | Value::ConstSym(id) | Value::GlobSym(id) => {
+                        let symbol = symbol_map.entry(self.clone()).or_insert_with(|| {
+                            let result = *id as u32;
+                            Symbol::Int(result)
+                        });
+                        Some(ast::BV::new_const(z3_ctx, symbol.clone(), width))
+                    }
+                    _ => {
+                        let symbol = symbol_map.entry(self.clone()).or_insert_with(|| {
+                            let result = *symbol_id;
+                            *symbol_id += 1;
+                            Symbol::Int(result)
+                        });
+                        Some(ast::BV::new_const(z3_ctx, symbol.clone(), width))
+                    }
+                }
+            }
+
+            // Encode the value as a z3 boolean under the bit-vector encoding
+            pub fn into_z3_bv_bool<'ctx>(
+                &self,
+                int_widths: &IntWidths,
+                symbol_map: &mut HashMap<Value, z3::Symbol>,
+                symbol_id: &mut u32,
+                z3_ctx: &'ctx z3::Context,
+            ) -> Option<z3::ast::Bool<'ctx>> {
+                use z3::ast::Ast;
+                match self.as_comparison() {
+                    Some(comparison) => comparison.into_z3_bv(int_widths, symbol_map, symbol_id, z3_ctx),
+                    None => self
+                        .into_z3_bv(1, int_widths, &HashMap::new(), symbol_map, symbol_id, z3_ctx)
+                        .map(|v| v._eq(&z3::ast::BV::from_i64(z3_ctx, 0, 1)).not()),
+                }
+            }
         }
 
         #[derive(Debug, Clone)]
@@ -233,6 +416,10 @@ macro_rules! decl_value_with_wrapper {
         }
 
         impl Comparison {
+            pub fn new(pred: Predicate, op0: $wrapper<Value>, op1: $wrapper<Value>) -> Self {
+                Self { pred, op0, op1 }
+            }
+
             pub fn into_z3_ast<'ctx>(
                 &self,
                 symbol_map: &mut HashMap<Value, z3::Symbol>,
@@ -256,111 +443,138 @@ macro_rules! decl_value_with_wrapper {
                 }
             }
 
+            pub fn as_value(&self) -> Value {
+                Value::ICmp {
+                    pred: self.pred,
+                    op0: self.op0.clone(),
+                    op1: self.op1.clone(),
+                }
+            }
+
+            // Encode the comparison over bit-vectors, so that signed and unsigned predicates
+            // are exact and the arithmetic wraps on overflow
+            pub fn into_z3_bv<'ctx>(
+                &self,
+                int_widths: &IntWidths,
+                symbol_map: &mut HashMap<Value, z3::Symbol>,
+                symbol_id: &mut u32,
+                z3_ctx: &'ctx z3::Context,
+            ) -> Option<z3::ast::Bool<'ctx>> {
+                use z3::ast::Ast;
+                let comparison = self.as_value();
+                let width = int_widths.comparison_width(&comparison).unwrap_or(DEFAULT_INT_WIDTH);
+                let no_extensions = HashMap::new();
+                let extensions = int_widths.extensions(&comparison).unwrap_or(&no_extensions);
+                let Comparison { pred, op0, op1 } = self;
+                let z3_op0 = op0.into_z3_bv(width, int_widths, extensions, symbol_map, symbol_id, z3_ctx);
+                let z3_op1 = op1.into_z3_bv(width, int_widths, extensions, symbol_map, symbol_id, z3_ctx);
+                match (z3_op0, z3_op1) {
+                    (Some(op0), Some(op1)) => match pred {
+                        Predicate::EQ => Some(op0._eq(&op1)),
+                        Predicate::NE => Some(op0._eq(&op1).not()),
+                        Predicate::SGE => Some(op0.bvsge(&op1)),
+                        Predicate::UGE => Some(op0.bvuge(&op1)),
+                        Predicate::SGT => Some(op0.bvsgt(&op1)),
+                        Predicate::UGT => Some(op0.bvugt(&op1)),
+                        Predicate::SLE => Some(op0.bvsle(&op1)),
+                        Predicate::ULE => Some(op0.bvule(&op1)),
+                        Predicate::SLT => Some(op0.bvslt(&op1)),
+                        Predicate::ULT => Some(op0.bvult(&op1)),
+                    },
+                    _ => None,
+                }
+            }
+
+            // Evaluate the comparison if both of the operands are constants,
+            // where the operands may be selects over constants
+            pub fn eval_constant_cond(&self) -> Option<bool> {
+                let Comparison { pred, op0, op1 } = self;
+                match (op0.eval_constant_value(), op1.eval_constant_value()) {
+                    // The constants are sign-extended, which keeps their unsigned order
+                    (Some(i), Some(j)) => Some(match pred {
+                        Predicate::EQ => i == j,
+                        Predicate::NE => i != j,
+                        Predicate::SGE => i >= j,
+                        Predicate::SGT => i > j,
+                        Predicate::SLE => i <= j,
+                        Predicate::SLT => i < j,
+                        Predicate::UGE => i as u64 >= j as u64,
+                        Predicate::UGT => i as u64 > j as u64,
+                        Predicate::ULE => i as u64 <= j as u64,
+                        Predicate::ULT => (i as u64) < j as u64,
+                    }),
+                    _ => None,
+                }
+            }
+
+            // Whether the branch can be taken, which is always the case unless the comparison is constant
             pub fn evaluate_cond<'ctx>(
                 &self,
                 then: bool,
             ) -> bool {
-                let Comparison { pred, op0, op1 } = self;
-                let op0 = op0.eval_constant_value();
-                let op1 = op1.eval_constant_value();
-                match (op0, op1) {
-                    (Some(i), Some(j)) => match pred {
-                        Predicate::EQ => {
-                            if i == j {
-                                if then {
-                                    true
-                                } else {
-                                    false
-                                }
-                            } else {
-                                if then {
-                                    false
-                                } else {
-                                    true
-                                }
-                            }
-                        },
-                        Predicate::NE => {
-                            if i != j {
-                                if then {
-                                    true
-                                } else {
-                                    false
-                                }
-                            } else {
-                                if then {
-                                    false
-                                } else {
-                                    true
-                                }
-                            }
-                        },
-                        Predicate::SGE | Predicate::UGE => {
-                            if i >= j {
-                                if then {
-                                    true
-                                } else {
-                                    false
-                                }
-                            } else {
-                                if then {
-                                    false
-                                } else {
-                                    true
-                                }
-                            }
-                        },
-                        Predicate::SGT | Predicate::UGT => {
-                            if i > j {
-                                if then {
-                                    true
-                                } else {
-                                    false
-                                }
-                            } else {
-                                if then {
-                                    false
-                                } else {
-                                    true
-                                }
-                            }
-                        },
-                        Predicate::SLE | Predicate::ULE => {
-                            if i <= j {
-                                if then {
-                                    true
-                                } else {
-                                    false
-                                }
-                            } else {
-                                if then {
-                                    false
-                                } else {
-                                    true
-                                }
-                            }
-                        },
-                        Predicate::SLT | Predicate::ULT => {
-                            if i < j {
-                                if then {
-                                    true
-                                } else {
-                                    false
-                                }
-                            } else {
-                                if then {
-                                    false
-                                } else {
-                                    true
-                                }
-                            }
-                        },
-                    },
-                    _ => true,
+                match self.eval_constant_cond() {
+                    Some(cond) => cond == then,
+                    None => true,
                 }
             }
         }
 
+        // The bit widths of the integer values for the bit-vector encoding
+        #[derive(Debug, Clone, Default)]
+        pub struct IntWidths {
+            // The width of each integer value where it is produced. A cast yields the same value,
+            // so the first width is kept, e.g., the width of `x` rather than `(long) x`
+            values: HashMap<Value, u32>,
+            // The width of the operands of each comparison
+            comparisons: HashMap<Value, u32>,
+            // The extensions of the casts feeding each comparison, keyed by the extended values. A cast
+            // yields the same value, so the kind is kept per comparison the cast result is used in rather
+            // than per value, e.g., `x` may be sign-extended in one comparison and zero-extended in another
+            extensions: HashMap<Value, HashMap<Value, Extension>>,
+        }
+
+        impl IntWidths {
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            pub fn insert_value(&mut self, value: &Value, width: u32) {
+                match value {
+                    // Constants are shared by all the widths, and comparisons are booleans
+                    Value::Int(_) | Value::Null | Value::ICmp { .. } => {}
+                    _ => {
+                        self.values.entry(value.clone()).or_insert(width);
+                    }
+                }
+            }
+
+            pub fn insert_comparison(&mut self, comparison: Value, width: u32) {
+                self.comparisons.insert(comparison, width);
+            }
+
+            pub fn insert_extension(&mut self, comparison: Value, value: &Value, extension: Extension) {
+                match value {
+                    // Constants are extended when created
+                    Value::Int(_) | Value::Null => {}
+                    _ => {
+                        self.extensions.entry(comparison).or_default().insert(value.clone(), extension);
+                    }
+                }
+            }
+
+            pub fn value_width(&self, value: &Value) -> Option<u32> {
+                self.values.get(value).cloned()
+            }
+
+            pub fn comparison_width(&self, comparison: &Value) -> Option<u32> {
+                self.comparisons.get(comparison).cloned()
+            }
+
+            pub fn extensions(&self, comparison: &Value) -> Option<&HashMap<Value, Extension>> {
+                self.extensions.get(comparison)
+            }
+        }
+
         #[derive(Debug, Clone, Serialize, Deserialize)]
         pub enum Semantics {
             Call {
@@ -379,6 +593,12 @@ macro_rules! decl_value_with_wrapper {
             },
             Switch {
                 cond: $wrapper<Value>,
+                // All the case values of the switch
+                #[serde(default)]
+                cases: Vec<i64>,
+                // The case values going to the taken destination, empty for the default destination
+                #[serde(default)]
+                taken_cases: Vec<i64>,
             },
             Ret {
                 op: Option<$wrapper<Value>>,
@@ -405,6 +625,11 @@ macro_rules! decl_value_with_wrapper {
                 op0: $wrapper<Value>,
                 op1: $wrapper<Value>,
             },
+            Select {
+                cond: $wrapper<Value>,
+                true_val: $wrapper<Value>,
+                false_val: $wrapper<Value>,
+            },
         }
 
         impl Semantics {
@@ -414,6 +639,42 @@ macro_rules! decl_value_with_wrapper {
                     _ => panic!("Target is not a call"),
                 }
             }
+
+            // The operands keyed by their field names, with the position for the lists, e.g., `args.0`
+            pub fn operands(&self) -> Vec<(String, &$wrapper<Value>)> {
+                fn list<'a>(name: &str, values: &'a [$wrapper<Value>]) -> Vec<(String, &'a $wrapper<Value>)> {
+                    values.iter().enumerate().map(|(i, v)| (format!("{}.{}", name, i), v)).collect()
+                }
+                match self {
+                    Semantics::Call { func, args } => {
+                        let mut operands = vec![("func".to_string(), func)];
+                        operands.extend(list("args", args));
+                        operands
+                    }
+                    Semantics::ICmp { op0, op1, .. } | Semantics::Bin { op0, op1, .. } => {
+                        vec![("op0".to_string(), op0), ("op1".to_string(), op1)]
+                    }
+                    Semantics::CondBr { cond, .. } | Semantics::Switch { cond, .. } => vec![("cond".to_string(), cond)],
+                    Semantics::Ret { op } => op.iter().map(|op| ("op".to_string(), op)).collect(),
+                    Semantics::Store { loc, val } => vec![("loc".to_string(), loc), ("val".to_string(), val)],
+                    Semantics::Load { loc } => vec![("loc".to_string(), loc)],
+                    Semantics::GEP { loc, indices } => {
+                        let mut operands = vec![("loc".to_string(), loc)];
+                        operands.extend(list("indices", indices));
+                        operands
+                    }
+                    Semantics::Una { op0, .. } => vec![("op0".to_string(), op0)],
+                    Semantics::Select {
+                        cond,
+                        true_val,
+                        false_val,
+                    } => vec![
+                        ("cond".to_string(), cond),
+                        ("true_val".to_string(), true_val),
+                        ("false_val".to_string(), false_val),
+                    ],
+                }
+            }
         }
     };
 }
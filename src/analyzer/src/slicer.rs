@@ -3,13 +3,12 @@ use petgraph::{graph::*, visit::*, Direction};
 use rayon::prelude::*;
 use regex::Regex;
 use serde_json::json;
-use std::collections::{HashMap, HashSet};
-use std::fs;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::PathBuf;
 
 use crate::call_graph::*;
 use crate::options::*;
-use crate::utils::*;
+use crate::storage::*;
 
 pub trait SlicerOptions: GeneralOptions + Send + Sync {
     fn slice_depth(&self) -> usize;
@@ -29,12 +28,14 @@ pub struct Slice<'ctx> {
     pub caller: Function<'ctx>,
     pub call_chain: CallGraphPath<'ctx>,
     pub callee: Function<'ctx>,
-    pub instr: CallInstruction<'ctx>,
-    pub functions: HashSet<(Function<'ctx>, CallInstruction<'ctx>)>,
+    pub instr: CallBaseInstruction<'ctx>,
+    pub functions: HashSet<(Function<'ctx>, CallBaseInstruction<'ctx>)>,
+    // How the callee of the target call is resolved
+    pub kind: CallEdgeKind,
 }
 
 impl<'ctx> Slice<'ctx> {
-    pub fn contains(&self, f: (Function<'ctx>, CallInstruction<'ctx>)) -> bool {
+    pub fn contains(&self, f: (Function<'ctx>, CallBaseInstruction<'ctx>)) -> bool {
         self.functions.contains(&f)
     }
 
@@ -51,11 +52,22 @@ impl<'ctx> Slice<'ctx> {
             "instr": self.instr.debug_loc_string(),
             "functions": self.functions.iter().map(|(f, instr)|
                                                     (f.simp_name(), instr.debug_loc_string()))
-                                                .collect::<HashSet<_>>(),
+                                                .collect::<BTreeSet<_>>(),
             "call_chain": call_chain,
+            "kind": self.kind.to_str(),
         })
     }
 
+    // The callee of a call instruction in the call chain (or the target one), which is
+    // how the indirect calls in the slice are resolved
+    pub fn resolved_callee(&self, instr: CallBaseInstruction<'ctx>) -> Option<Function<'ctx>> {
+        if instr == self.instr {
+            Some(self.callee)
+        } else {
+            self.call_chain.succ.iter().find(|(i, _)| *i == instr).map(|(_, f)| *f)
+        }
+    }
+
     pub fn target_function_name(&self) -> String {
         self.callee.simp_name()
     }
@@ -63,6 +75,50 @@ impl<'ctx> Slice<'ctx> {
     pub fn size(&self) -> usize {
         self.functions.len()
     }
+
+    pub fn num_blocks(&self) -> usize {
+        num_blocks_of_slice(&self.call_chain, &self.functions)
+    }
+}
+
+// The slice dropped or trimmed by the slicer, together with the reason
+#[derive(Debug, Clone)]
+pub struct SkippedSlice {
+    pub target: String,
+    pub caller: String,
+    pub callee: String,
+    pub instr: String,
+    pub num_blocks: usize,
+    pub reason: String,
+    pub trimmed: bool,
+}
+
+impl SkippedSlice {
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "target": self.target,
+            "caller": self.caller,
+            "callee": self.callee,
+            "instr": self.instr,
+            "num_blocks": self.num_blocks,
+            "reason": self.reason,
+        })
+    }
+}
+
+// Count the blocks of the functions that may be executed in a slice, i.e.,
+// the functions in call chain (except for the target) and the related functions
+fn num_blocks_of_slice<'ctx>(
+    call_chain: &CallGraphPath<'ctx>,
+    functions: &HashSet<(Function<'ctx>, CallBaseInstruction<'ctx>)>,
+) -> usize {
+    let mut funcs = HashSet::new();
+    funcs.insert(call_chain.begin);
+    if call_chain.succ.len() > 1 {
+        funcs.extend(call_chain.succ[..call_chain.succ.len() - 1].iter().map(|(_, f)| *f));
+    }
+    funcs.extend(functions.iter().map(|(f, _)| *f));
+    funcs.iter().map(|f| f.num_blocks()).sum()
 }
 
 enum TargetFilter {
@@ -171,9 +227,9 @@ pub trait TargetSlicesMapTrait<'ctx>: Sized {
         target_edges_map: &TargetEdgesMap,
         call_graph: &CallGraph<'ctx>,
         options: &impl SlicerOptions,
-    ) -> Self;
+    ) -> (Self, Vec<SkippedSlice>);
 
-    fn dump<O>(&self, options: &O)
+    fn dump<O>(&self, options: &O, storage: &dyn Storage)
     where
         O: SlicerOptions + IOOptions;
 }
@@ -183,24 +239,34 @@ impl<'ctx> TargetSlicesMapTrait<'ctx> for TargetSlicesMap<'ctx> {
         target_edges_map: &TargetEdgesMap,
         call_graph: &CallGraph<'ctx>,
         options: &impl SlicerOptions,
-    ) -> Self {
+    ) -> (Self, Vec<SkippedSlice>) {
         let mut result = HashMap::new();
+        let mut skipped_slices = vec![];
         for (target, edges) in target_edges_map {
-            let slices = call_graph.slices_of_call_edges(&edges[..], options);
+            let (slices, skipped) = call_graph.slices_of_call_edges(&edges[..], options);
             result.insert(target.clone(), slices);
+            skipped_slices.extend(skipped.into_iter().map(|s| SkippedSlice {
+                target: target.clone(),
+                ..s
+            }));
         }
-        result
+        // The map is iterated in arbitrary order, so sort by target and edge to keep the records reproducible
+        skipped_slices.sort_by(|a, b| {
+            (&a.target, &a.caller, &a.callee, &a.instr, &a.reason)
+                .cmp(&(&b.target, &b.caller, &b.callee, &b.instr, &b.reason))
+        });
+        (result, skipped_slices)
     }
 
-    fn dump<O>(&self, options: &O)
+    fn dump<O>(&self, options: &O, storage: &dyn Storage)
     where
         O: SlicerOptions + IOOptions,
     {
         for (target, slices) in self {
-            fs::create_dir_all(options.slice_target_dir(target.as_str())).expect("Cannot create slice folder");
             slices.par_iter().enumerate().for_each(|(i, slice)| {
-                let path = options.slice_target_file_path(target.as_str(), i);
-                dump_json(&slice.to_json(), path).expect("Cannot dump slice json");
+                storage
+                    .dump_slice(target.as_str(), options.basename_of_bc_file(), i, &slice.to_json())
+                    .expect("Cannot dump slice json");
             });
         }
     }
@@ -226,14 +292,26 @@ impl TargetNumSlicesMapTrait for TargetNumSlicesMap {
 }
 
 pub trait Slicer<'ctx> {
-    fn slices_of_call_edges(&self, edges: &[EdgeIndex], options: &impl SlicerOptions) -> Vec<Slice<'ctx>>;
+    fn slices_of_call_edges(
+        &self,
+        edges: &[EdgeIndex],
+        options: &impl SlicerOptions,
+    ) -> (Vec<Slice<'ctx>>, Vec<SkippedSlice>);
 
-    fn generate_slices(&self, edge_id: EdgeIndex, options: &impl SlicerOptions) -> Vec<Slice<'ctx>>;
+    fn generate_slices(
+        &self,
+        edge_id: EdgeIndex,
+        options: &impl SlicerOptions,
+    ) -> (Vec<Slice<'ctx>>, Vec<SkippedSlice>);
 }
 
 impl<'ctx> Slicer<'ctx> for CallGraph<'ctx> {
     // Generate slices
-    fn generate_slices(&self, edge_id: EdgeIndex, options: &impl SlicerOptions) -> Vec<Slice<'ctx>> {
+    fn generate_slices(
+        &self,
+        edge_id: EdgeIndex,
+        options: &impl SlicerOptions,
+    ) -> (Vec<Slice<'ctx>>, Vec<SkippedSlice>) {
         // Basic information
         let mut slice_call_chains: Vec<CallGraphPath<'ctx>> = vec![];
         let instr = self.graph[edge_id];
@@ -243,22 +321,32 @@ impl<'ctx> Slicer<'ctx> for CallGraph<'ctx> {
         };
 
         if caller == callee || instr.debug_loc_string() == "" {
-            return vec![];
-        }
-
-        // get the target function index in caller
-        let mut index = self.graph.edges(caller_id).count() - 1;
-        for edge in self.graph.edges(caller_id) {
-            // the result is reverse with the result of itering the function...
-            if edge.id() == edge_id {
-                break;
-            }
-            index -= 1;
+            return (vec![], vec![]);
         }
 
         let mut target_is_returned = false;
         // Get directly related functions
-        let related_funcs = direct_related_funcs(&self.graph[caller_id], index, &mut target_is_returned);
+        let related_funcs = direct_related_funcs(&self.graph[caller_id], (callee, instr), &mut target_is_returned);
+        let functions: HashSet<_> = related_funcs.iter().map(|func| *func).collect();
+
+        // Skip the edge if even the caller alone exceeds the maximum number of blocks
+        let caller_chain = CallGraphPath {
+            begin: caller,
+            succ: vec![(instr, callee)],
+        };
+        let num_blocks = num_blocks_of_slice(&caller_chain, &functions);
+        if num_blocks > options.max_num_blocks() {
+            let skipped = SkippedSlice {
+                target: String::new(),
+                caller: caller.simp_name(),
+                callee: callee.simp_name(),
+                instr: instr.debug_loc_string(),
+                num_blocks,
+                reason: format!("exceeding max number of blocks ({})", options.max_num_blocks()),
+                trimmed: false,
+            };
+            return (vec![], vec![skipped]);
+        }
         // Set up the init slice depth
         let mut init_depth = options.slice_depth();
         if is_wrapper_function(&self.graph[caller_id]) // || target_is_returned
@@ -284,6 +372,10 @@ impl<'ctx> Slicer<'ctx> for CallGraph<'ctx> {
             } else {
                 let mut contains_parent = false;
                 for incoming_edge in self.graph.edges_directed(func_id, Direction::Incoming) {
+                    // Do not extend the call chain with callers only matched by type signature
+                    if self.edge_kind(incoming_edge.id()) == CallEdgeKind::TypeSignature {
+                        continue;
+                    }
                     let new_caller_id = incoming_edge.source();
                     if !callers.contains(&new_caller_id) {
                         contains_parent = true;
@@ -305,35 +397,80 @@ impl<'ctx> Slicer<'ctx> for CallGraph<'ctx> {
         }
 
         // Return slices
-        let mut slices = Vec::new();
-        for call_chain in slice_call_chains {
+        let mut slices: Vec<Slice<'ctx>> = Vec::new();
+        let mut trimmed_slices = vec![];
+        for mut call_chain in slice_call_chains {
+            // Trim the outer callers until the slice fits in the maximum number of blocks,
+            // which terminates at the caller since it is checked above
+            let original_begin = call_chain.begin;
+            let num_blocks = num_blocks_of_slice(&call_chain, &functions);
+            while call_chain.succ.len() > 1 && num_blocks_of_slice(&call_chain, &functions) > options.max_num_blocks() {
+                let (_, new_begin) = call_chain.succ.remove(0);
+                call_chain.begin = new_begin;
+            }
+            if call_chain.begin != original_begin {
+                trimmed_slices.push(SkippedSlice {
+                    target: String::new(),
+                    caller: caller.simp_name(),
+                    callee: callee.simp_name(),
+                    instr: instr.debug_loc_string(),
+                    num_blocks,
+                    reason: format!(
+                        "trimmed from {} to {} for exceeding max number of blocks ({})",
+                        original_begin.simp_name(),
+                        call_chain.begin.simp_name(),
+                        options.max_num_blocks()
+                    ),
+                    trimmed: true,
+                });
+            }
+            // Different call chains may be trimmed to the same one
+            if slices
+                .iter()
+                .any(|s| s.call_chain.begin == call_chain.begin && s.call_chain.succ == call_chain.succ)
+            {
+                continue;
+            }
             let entry = call_chain.begin;
-            let functions = related_funcs.iter().map(|func| *func).collect();
             let slice = Slice {
                 caller,
                 call_chain,
                 callee,
                 instr,
                 entry,
-                functions,
+                functions: functions.clone(),
+                kind: self.edge_kind(edge_id),
             };
             slices.push(slice)
         }
-        slices
+        (slices, trimmed_slices)
     }
 
-    fn slices_of_call_edges(&self, edges: &[EdgeIndex], options: &impl SlicerOptions) -> Vec<Slice<'ctx>> {
-        let f = |edge_id: &EdgeIndex| -> Vec<Slice<'ctx>> { self.generate_slices(edge_id.clone(), options) };
-        if options.use_serial() {
-            edges.iter().map(f).flatten().collect()
+    fn slices_of_call_edges(
+        &self,
+        edges: &[EdgeIndex],
+        options: &impl SlicerOptions,
+    ) -> (Vec<Slice<'ctx>>, Vec<SkippedSlice>) {
+        let f = |edge_id: &EdgeIndex| -> (Vec<Slice<'ctx>>, Vec<SkippedSlice>) {
+            self.generate_slices(edge_id.clone(), options)
+        };
+        let results: Vec<_> = if options.use_serial() {
+            edges.iter().map(f).collect()
         } else {
-            edges.par_iter().map(f).flatten().collect()
+            edges.par_iter().map(f).collect()
+        };
+        let mut slices = vec![];
+        let mut skipped_slices = vec![];
+        for (s, k) in results {
+            slices.extend(s);
+            skipped_slices.extend(k);
         }
+        (slices, skipped_slices)
     }
 }
 
 //* Begin: find related functions in caller *//
-fn get_args<'ctx>(call_instr: &CallInstruction<'ctx>) -> HashSet<Operand<'ctx>> {
+fn get_args<'ctx>(call_instr: &CallBaseInstruction<'ctx>) -> HashSet<Operand<'ctx>> {
     let mut oprands = HashSet::new();
     for arg in call_instr.arguments() {
         match arg {
@@ -348,23 +485,32 @@ fn get_args<'ctx>(call_instr: &CallInstruction<'ctx>) -> HashSet<Operand<'ctx>>
 
 fn direct_related_funcs<'ctx>(
     caller: &Function<'ctx>,
-    index: usize,
+    target: (Function<'ctx>, CallBaseInstruction<'ctx>),
     target_is_returned: &mut bool,
-) -> HashSet<(Function<'ctx>, CallInstruction<'ctx>)> {
-    let mut related_funcs: HashSet<(Function<'ctx>, CallInstruction<'ctx>)> = HashSet::new();
+) -> HashSet<(Function<'ctx>, CallBaseInstruction<'ctx>)> {
+    let mut related_funcs: HashSet<(Function<'ctx>, CallBaseInstruction<'ctx>)> = HashSet::new();
     let mut var_map: HashMap<Operand<'ctx>, HashSet<Operand<'ctx>>> = HashMap::new(); // {Loc: [potential_values...]}
     let mut functions = vec![]; // each call in the caller: [(function, callinstr), ...]
     let mut func_args = vec![]; // arguments of each call in the caller
     let mut func_ret = vec![]; // return of each call in the caller
     let mut caller_ret = HashSet::new(); // return of the caller
+    let mut index = None; // index of the target call in functions
 
     // flow-insensitve: record all possible propagation locations of the variables linearly
     for b in caller.iter_blocks() {
         for instr in b.iter_instructions() {
             match instr {
-                Instruction::Call(call_instr) => {
+                Instruction::Call(_) | Instruction::Invoke(_) => {
+                    let call_instr = CallBaseInstruction::from_instruction(instr).unwrap();
                     if !call_instr.is_intrinsic_call() {
-                        match call_instr.callee_function() {
+                        // The target call may be an indirect one
+                        let callee = if call_instr == target.1 {
+                            index = Some(functions.len());
+                            Some(target.0)
+                        } else {
+                            call_instr.callee_function()
+                        };
+                        match callee {
                             Some(callee) => {
                                 functions.push((callee, call_instr));
                                 func_args.push(get_args(&call_instr));
@@ -479,6 +625,11 @@ fn direct_related_funcs<'ctx>(
         }
     }
 
+    let index = match index {
+        Some(index) => index,
+        None => return related_funcs,
+    };
+
     // determine whether the target is returned by the caller
     *target_is_returned = !caller_ret.is_disjoint(&func_ret[index]);
 
@@ -515,7 +666,7 @@ fn is_wrapper_function<'ctx>(f: &Function<'ctx>) -> bool {
                     if ret_instr.has_op() {
                         match ret_instr.op().unwrap() {
                             Operand::Instruction(instr) => match instr {
-                                Instruction::Call(_) => {
+                                Instruction::Call(_) | Instruction::Invoke(_) => {
                                     result = true;
                                 }
                                 _ => {}
@@ -526,7 +677,8 @@ fn is_wrapper_function<'ctx>(f: &Function<'ctx>) -> bool {
                         result = true;
                     }
                 }
-                Instruction::Call(call_instr) => {
+                Instruction::Call(_) | Instruction::Invoke(_) => {
+                    let call_instr = CallBaseInstruction::from_instruction(i).unwrap();
                     if call_instr.is_intrinsic_call() {
                         continue;
                     }
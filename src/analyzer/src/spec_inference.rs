@@ -0,0 +1,561 @@
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::feature_extraction::*;
+use crate::feature_extractors::*;
+use crate::options::*;
+use crate::storage::*;
+use crate::utils::*;
+
+// The frequency a statistic needs to reach to become a specification
+#[derive(Debug, Copy, Clone)]
+pub enum ThresholdFunction {
+    Fixed(f64),
+    // Grow from 0.5 to 0.8 with the number of traces, centered at `rho`
+    Sigmoid { rho: f64 },
+}
+
+impl ThresholdFunction {
+    // The same as `get_threshold` of the python feature analyzer: a threshold within (0, 1) is used
+    // as is, otherwise the sigmoid with `rho` (500 by default, or the fixed 0.8 if it is 0) is used
+    pub fn new(threshold: Option<f64>, rho: Option<f64>) -> Self {
+        match (threshold, rho) {
+            (Some(threshold), _) if threshold > 0.0 && threshold < 1.0 => Self::Fixed(threshold),
+            (_, Some(rho)) if rho == 0.0 => Self::Fixed(0.8),
+            (_, rho) => Self::Sigmoid {
+                rho: rho.unwrap_or(500.0),
+            },
+        }
+    }
+
+    pub fn threshold(&self, num_traces: usize) -> f64 {
+        match *self {
+            Self::Fixed(threshold) => threshold,
+            Self::Sigmoid { rho } => 0.5 + 0.3 / (1.0 + (-(num_traces as f64 - rho) / (rho / 5.0)).exp()),
+        }
+    }
+}
+
+pub trait SpecInferenceOptions: IOOptions + Send + Sync {
+    fn threshold_function(&self) -> ThresholdFunction {
+        ThresholdFunction::new(None, None)
+    }
+
+    // Count the same features at a location only once
+    fn remove_dup(&self) -> bool {
+        false
+    }
+}
+
+// The identical features of a target, the number of traces having them and their locations
+pub struct AggregatedFeatures {
+    pub features: TraceFeatures,
+    pub time: usize,
+    pub locs: Vec<String>,
+}
+
+pub struct TargetFeatures {
+    pub target: String,
+    pub features: Vec<AggregatedFeatures>,
+}
+
+impl TargetFeatures {
+    pub fn aggregate<I>(target: &str, features: I, remove_dup: bool) -> Self
+    where
+        I: IntoIterator<Item = TraceFeatures>,
+    {
+        let mut aggregated: Vec<AggregatedFeatures> = vec![];
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        for mut features in features {
+            // Skip the features which do not record the location
+            if features.loc.is_empty() {
+                continue;
+            }
+            let loc = std::mem::take(&mut features.loc);
+            let key = features.to_json().to_string();
+            match indices.get(&key) {
+                Some(&i) => {
+                    let agg = &mut aggregated[i];
+                    if !agg.locs.contains(&loc) {
+                        agg.locs.push(loc);
+                        agg.time += 1;
+                    } else if !remove_dup {
+                        agg.time += 1;
+                    }
+                }
+                None => {
+                    indices.insert(key, aggregated.len());
+                    aggregated.push(AggregatedFeatures {
+                        features,
+                        time: 1,
+                        locs: vec![loc],
+                    });
+                }
+            }
+        }
+        Self {
+            target: target.to_string(),
+            features: aggregated,
+        }
+    }
+
+    pub fn load(storage: &dyn Storage, target: &str, remove_dup: bool) -> Self {
+        let mut num_outdated = 0;
+        let features = storage
+            .load_target_features(target)
+            .into_iter()
+            .filter_map(|json| {
+                // The features of another version are not comparable with, or even parsable as, the current ones
+                if let Ok(json) = &json {
+                    if json.get("version").and_then(|version| version.as_u64()) != Some(FEATURE_VERSION as u64) {
+                        num_outdated += 1;
+                        return None;
+                    }
+                }
+                let features =
+                    json.and_then(|json| serde_json::from_value::<TraceFeatures>(json).map_err(|x| format!("{:?}", x)));
+                match features {
+                    Ok(features) => Some(features),
+                    Err(err) => {
+                        println!("Can't parse features of {}: {}", target, err);
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        if num_outdated > 0 {
+            println!(
+                "Skipped {} traces of {} with features not of version {}, please extract the features again",
+                num_outdated, target, FEATURE_VERSION
+            );
+        }
+        Self::aggregate(target, features, remove_dup)
+    }
+
+    pub fn num_traces(&self) -> usize {
+        self.features.iter().map(|agg| agg.time).sum()
+    }
+}
+
+fn round3(x: f64) -> f64 {
+    (x * 1000.0).round() / 1000.0
+}
+
+// `[need to check, frequency]` for each argument, and the frequent relations between them
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ArgumentSpecification {
+    pub args_need_to_check: Vec<(bool, f64)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relations: Vec<ArgumentRelationSpecification>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ArgumentRelationSpecification {
+    pub args: Vec<usize>,
+    pub score: f64,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ReturnValueSpecification {
+    pub need_to_check: (bool, f64),
+    // The check values given by `check_value`, with their frequencies among the checks
+    pub valid_chkvals: BTreeMap<String, f64>,
+    pub no_need_to_check_if_same_in_post: bool,
+    pub no_same_in_post_need_to_check: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CausalitySpecification {
+    pub pre_functions: BTreeMap<String, f64>,
+    // `[frequency, {check condition of the target: frequency}]`
+    pub post_functions: BTreeMap<String, (f64, BTreeMap<String, f64>)>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SpecificationInfo {
+    pub threshold: f64,
+    pub traces_num: usize,
+}
+
+// The specification of a target, in the shape of the one of the python feature analyzer
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Specification {
+    pub causal: CausalitySpecification,
+    #[serde(rename = "arg.pre")]
+    pub arg_pre: ArgumentSpecification,
+    #[serde(rename = "arg.post")]
+    pub arg_post: ArgumentSpecification,
+    pub ret: ReturnValueSpecification,
+    pub info: SpecificationInfo,
+}
+
+impl Specification {
+    // `None` if there is no features of the target
+    pub fn infer(target_features: &TargetFeatures, threshold_function: ThresholdFunction) -> Option<Self> {
+        let sum_time = target_features.num_traces();
+        if sum_time == 0 {
+            return None;
+        }
+        let threshold = threshold_function.threshold(sum_time);
+
+        let mut causality = CausalityAnalyzer::default();
+        let mut arg_pre = ArgumentPreconditionAnalyzer::default();
+        let mut arg_post = ArgumentPostconditionAnalyzer::default();
+        let mut retval = ReturnValueAnalyzer::default();
+        for AggregatedFeatures { features, time, .. } in &target_features.features {
+            causality.update(features, *time);
+            arg_pre.update(features, *time);
+            arg_post.update(features, *time);
+            retval.update(&target_features.target, features, *time);
+        }
+
+        Some(Self {
+            causal: causality.specification(&target_features.target, sum_time, threshold),
+            arg_pre: arg_pre.specification(sum_time, threshold),
+            arg_post: arg_post.specification(sum_time, threshold),
+            ret: retval.specification(sum_time, threshold, arg_pre.arg_num),
+            info: SpecificationInfo {
+                threshold,
+                traces_num: sum_time,
+            },
+        })
+    }
+}
+
+// Only the first number of arguments is considered, so as to skip the variadic calls
+fn same_arg_num(arg_num: &mut Option<usize>, cur_arg_num: usize) -> bool {
+    match *arg_num {
+        None => {
+            *arg_num = Some(cur_arg_num);
+            true
+        }
+        Some(arg_num) => arg_num != 0 && arg_num == cur_arg_num,
+    }
+}
+
+fn args_need_to_check(args_checked_count: &[usize], sum_time: usize, threshold: f64) -> Vec<(bool, f64)> {
+    args_checked_count
+        .iter()
+        .map(|count| {
+            let score = *count as f64 / sum_time as f64;
+            (score >= threshold, round3(score))
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct ArgumentPreconditionAnalyzer {
+    arg_num: Option<usize>,
+    args_checked_count: Vec<usize>,
+    relations_count: BTreeMap<Vec<usize>, usize>,
+}
+
+impl ArgumentPreconditionAnalyzer {
+    fn update(&mut self, features: &TraceFeatures, time: usize) {
+        let arg_pre = match &features.arg_pre {
+            Some(arg_pre) => arg_pre,
+            None => return,
+        };
+        if !same_arg_num(&mut self.arg_num, arg_pre.arg_num) {
+            return;
+        }
+        self.args_checked_count.resize(arg_pre.arg_num, 0);
+        for (i, arg) in arg_pre.feature.iter().enumerate() {
+            if arg.check.checked {
+                self.args_checked_count[i] += time;
+            }
+        }
+        if arg_pre.has_relation {
+            for relation in &arg_pre.relations {
+                *self.relations_count.entry(relation.clone()).or_default() += time;
+            }
+        }
+    }
+
+    fn specification(&self, sum_time: usize, threshold: f64) -> ArgumentSpecification {
+        ArgumentSpecification {
+            args_need_to_check: args_need_to_check(&self.args_checked_count, sum_time, threshold),
+            relations: self
+                .relations_count
+                .iter()
+                .map(|(args, count)| (args, *count as f64 / sum_time as f64))
+                .filter(|(_, score)| *score >= threshold)
+                .map(|(args, score)| ArgumentRelationSpecification {
+                    args: args.clone(),
+                    score: round3(score),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct ArgumentPostconditionAnalyzer {
+    arg_num: Option<usize>,
+    args_checked_count: Vec<usize>,
+}
+
+impl ArgumentPostconditionAnalyzer {
+    fn update(&mut self, features: &TraceFeatures, time: usize) {
+        let arg_post = match &features.arg_post {
+            Some(arg_post) => arg_post,
+            None => return,
+        };
+        if !same_arg_num(&mut self.arg_num, arg_post.arg_num) {
+            return;
+        }
+        // The features of the arguments are not of the same call if the numbers of arguments differ
+        if let Some(arg_pre) = &features.arg_pre {
+            if arg_pre.arg_num != arg_post.arg_num {
+                return;
+            }
+        }
+        self.args_checked_count.resize(arg_post.arg_num, 0);
+        for (i, arg) in arg_post.feature.iter().enumerate() {
+            // Only focus on the arguments which are not constants
+            let is_constant = features
+                .arg_pre
+                .as_ref()
+                .and_then(|arg_pre| arg_pre.feature.get(i))
+                .map_or(false, |arg_pre| arg_pre.is_constant);
+            if !is_constant && arg.used_in_check {
+                self.args_checked_count[i] += time;
+            }
+        }
+    }
+
+    fn specification(&self, sum_time: usize, threshold: f64) -> ArgumentSpecification {
+        ArgumentSpecification {
+            args_need_to_check: args_need_to_check(&self.args_checked_count, sum_time, threshold),
+            relations: vec![],
+        }
+    }
+}
+
+// The value the return value is checked against, e.g., `0` or `non_const`. A comparison like `> 0` is
+// regarded as one with `0.25`, the same as `>= 1`
+pub fn check_value(check: &ReturnValueCheck) -> Option<String> {
+    if check.checked {
+        Some(if check.compared_with_non_const {
+            "non_const".to_string()
+        } else {
+            match check.check_cond.as_str() {
+                "gt" | "le" => format!("{}", check.compared_with_const as f64 + 0.25),
+                "ge" | "lt" => format!("{}", check.compared_with_const as f64 - 0.25),
+                // A switch checking against a set of case values
+                "in" | "not_in" => format!("{}_{:?}", check.check_cond, check.checked_consts),
+                _ => check.compared_with_const.to_string(),
+            }
+        })
+    } else if check.indir_checked {
+        Some("indir_chk".to_string())
+    } else {
+        None
+    }
+}
+
+#[derive(Default)]
+struct ReturnValueAnalyzer {
+    checked_count: usize,
+    // Checked while the target is also in `post.call`
+    cur_checked: usize,
+    has_same_in_post: usize,
+    chkvals_count: BTreeMap<String, usize>,
+}
+
+impl ReturnValueAnalyzer {
+    fn update(&mut self, target: &str, features: &TraceFeatures, time: usize) {
+        let check = match &features.retval {
+            Some(retval) => &retval.check,
+            None => return,
+        };
+        let has_same_in_post = features
+            .causality
+            .as_ref()
+            .map_or(false, |causality| causality.post_call.contains_key(target));
+        if has_same_in_post {
+            self.has_same_in_post += time;
+        }
+        if let Some(chkval) = check_value(check) {
+            self.checked_count += time;
+            if has_same_in_post {
+                self.cur_checked += time;
+            }
+            *self.chkvals_count.entry(chkval).or_default() += time;
+        }
+    }
+
+    fn specification(&self, sum_time: usize, threshold: f64, arg_num: Option<usize>) -> ReturnValueSpecification {
+        let checked_ratio = self.checked_count as f64 / sum_time as f64;
+
+        // Only enabled if there can be only one same argument
+        let mut no_need_to_check_if_same_in_post = false;
+        let mut no_same_in_post_need_to_check = false;
+        if arg_num == Some(1) {
+            if self.has_same_in_post > 0 {
+                no_need_to_check_if_same_in_post = (self.cur_checked as f64 / self.has_same_in_post as f64) < threshold;
+            }
+            if sum_time > self.has_same_in_post {
+                no_same_in_post_need_to_check = (self.checked_count - self.cur_checked) as f64
+                    / (sum_time - self.has_same_in_post) as f64
+                    > threshold;
+            }
+        }
+
+        // The check values taking no less than the average share
+        let num_chkvals = self.chkvals_count.len() as f64;
+        let valid_chkvals = self
+            .chkvals_count
+            .iter()
+            .map(|(chkval, count)| (chkval, *count as f64 / self.checked_count as f64))
+            .filter(|(_, score)| *score >= 1.0 / num_chkvals)
+            .map(|(chkval, score)| (chkval.clone(), round3(score)))
+            .collect();
+
+        ReturnValueSpecification {
+            need_to_check: (checked_ratio >= threshold, round3(checked_ratio)),
+            valid_chkvals,
+            no_need_to_check_if_same_in_post,
+            no_same_in_post_need_to_check,
+        }
+    }
+}
+
+// The check condition of the return value in a trace, e.g., `0_eq`. It is spelled "defalut" for
+// the targets without return values, the same as the python feature analyzer
+pub fn chkval_cond(features: &TraceFeatures) -> String {
+    let check = match &features.retval {
+        Some(retval) => &retval.check,
+        None => return "defalut".to_string(),
+    };
+    if check.checked {
+        if check.compared_with_non_const {
+            "non_const".to_string()
+        } else {
+            match check.check_cond.as_str() {
+                "in" | "not_in" => format!("{:?}_{}", check.checked_consts, check.check_cond),
+                _ => format!("{}_{}", check.compared_with_const, check.check_cond),
+            }
+        }
+    } else if check.indir_checked {
+        "indir_chk".to_string()
+    } else {
+        "no_check".to_string()
+    }
+}
+
+#[derive(Default)]
+struct CausalityAnalyzer {
+    chkcond_count: HashMap<String, usize>,
+    pre_count: HashMap<String, usize>,
+    // The count of each post function, and the counts under the check conditions
+    post_count: HashMap<String, (usize, BTreeMap<String, usize>)>,
+}
+
+impl CausalityAnalyzer {
+    fn update(&mut self, features: &TraceFeatures, time: usize) {
+        let causality = match &features.causality {
+            Some(causality) => causality,
+            None => return,
+        };
+        let cond = chkval_cond(features);
+        *self.chkcond_count.entry(cond.clone()).or_default() += time;
+        for func in causality.pre_call.keys() {
+            *self.pre_count.entry(func.clone()).or_default() += time;
+        }
+        for func in causality.post_call.keys() {
+            let (count, cond_count) = self.post_count.entry(func.clone()).or_default();
+            *count += time;
+            *cond_count.entry(cond.clone()).or_default() += time;
+        }
+    }
+
+    // Prefer the post functions finishing with the object, e.g., `free` for `alloc`. Not for the
+    // small code bases with less than 50 traces
+    fn enhanced_score(target: &str, func: &str, sum_time: usize) -> f64 {
+        if sum_time < 50 {
+            0.0
+        } else if is_pre(target) && is_post(func) {
+            0.3
+        } else if is_post(func) {
+            0.1
+        } else {
+            0.0
+        }
+    }
+
+    // The check conditions under which the post function is called
+    fn post_conds(&self, cond_count: &BTreeMap<String, usize>, threshold: f64) -> BTreeMap<String, f64> {
+        let mut valid_len = cond_count.len();
+        let mut conds = BTreeMap::new();
+        // The `no_check` condition takes part only if it is the only one, or frequent enough
+        if let Some(count) = cond_count.get("no_check") {
+            let score = *count as f64 / self.chkcond_count["no_check"] as f64;
+            if valid_len == 1 || score > threshold {
+                conds.insert("no_check".to_string(), round3(score));
+            }
+            valid_len -= 1;
+        }
+        for (cond, count) in cond_count.iter().filter(|(cond, _)| *cond != "no_check") {
+            let score = *count as f64 / self.chkcond_count[cond] as f64;
+            if valid_len == 1 || score > threshold {
+                conds.insert(cond.clone(), round3(score));
+            }
+        }
+        conds
+    }
+
+    // Visit the functions from the most frequent ones, until they are called in less than 20% traces
+    fn frequent_functions<T, F>(stat: &HashMap<String, T>, count: F, sum_time: usize) -> Vec<(&String, &T, f64)>
+    where
+        F: Fn(&T) -> usize,
+    {
+        let mut funcs = stat.iter().collect::<Vec<_>>();
+        funcs.sort_by(|(f1, s1), (f2, s2)| (count(s2), f2).cmp(&(count(s1), f1)));
+        let mut result = vec![];
+        for (func, s) in funcs {
+            let ratio = count(s) as f64 / sum_time as f64;
+            result.push((func, s, ratio));
+            if ratio < 0.2 {
+                break;
+            }
+        }
+        result
+    }
+
+    fn specification(&self, target: &str, sum_time: usize, threshold: f64) -> CausalitySpecification {
+        let pre_functions = Self::frequent_functions(&self.pre_count, |count| *count, sum_time)
+            .into_iter()
+            .filter(|(_, _, score)| *score >= threshold)
+            .map(|(func, _, score)| (func.clone(), round3(score)))
+            .collect();
+        let post_functions = Self::frequent_functions(&self.post_count, |(count, _)| *count, sum_time)
+            .into_iter()
+            .map(|(func, stat, ratio)| (func, stat, ratio + Self::enhanced_score(target, func, sum_time)))
+            .filter(|(_, _, score)| *score >= threshold)
+            .map(|(func, (_, cond_count), score)| {
+                (func.clone(), (round3(score), self.post_conds(cond_count, threshold)))
+            })
+            .collect();
+        CausalitySpecification {
+            pre_functions,
+            post_functions,
+        }
+    }
+}
+
+// The specifications of the targets. Those without features are left out
+pub fn infer_specifications(
+    storage: &dyn Storage,
+    targets: &[String],
+    options: &impl SpecInferenceOptions,
+) -> BTreeMap<String, Specification> {
+    targets
+        .par_iter()
+        .filter_map(|target| {
+            let features = TargetFeatures::load(storage, target, options.remove_dup());
+            Specification::infer(&features, options.threshold_function()).map(|spec| (target.clone(), spec))
+        })
+        .collect()
+}
@@ -0,0 +1,568 @@
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::options::*;
+use crate::utils::*;
+
+// The backend storing slices, traces, features and metadata. Items are keyed by the target function,
+// the package (i.e., the name of the bc file, if any), the slice id and the trace id
+pub trait Storage: Send + Sync {
+    fn dump_slice(&self, target: &str, package: Option<&str>, slice_id: usize, slice: &Value) -> Result<(), String>;
+
+    fn load_slice(&self, target: &str, package: Option<&str>, slice_id: usize) -> Result<Value, String>;
+
+    fn num_slices(&self, target: &str, package: Option<&str>) -> usize;
+
+    // Prepare for the traces of a slice before executing it
+    fn init_traces(&self, target: &str, package: Option<&str>, slice_id: usize) -> Result<(), String>;
+
+    fn dump_trace(
+        &self,
+        target: &str,
+        package: Option<&str>,
+        slice_id: usize,
+        trace_id: usize,
+        trace: &Value,
+    ) -> Result<(), String>;
+
+    fn load_trace(
+        &self,
+        target: &str,
+        package: Option<&str>,
+        slice_id: usize,
+        trace_id: usize,
+    ) -> Result<Value, String>;
+
+    fn trace_ids(&self, target: &str, package: Option<&str>, slice_id: usize) -> Vec<usize>;
+
+    fn dump_features(
+        &self,
+        target: &str,
+        package: Option<&str>,
+        slice_id: usize,
+        trace_id: usize,
+        features: &Value,
+    ) -> Result<(), String>;
+
+    fn dump_metadata(&self, package: Option<&str>, metadata: &Value) -> Result<(), String>;
+
+    // Remove the traces of a slice, e.g., those left by an unfinished execution
+    fn clear_traces(&self, target: &str, package: Option<&str>, slice_id: usize) -> Result<(), String>;
+
+    // The record written once the execution of a slice finishes
+    fn dump_completion(
+        &self,
+        target: &str,
+        package: Option<&str>,
+        slice_id: usize,
+        record: &Value,
+    ) -> Result<(), String>;
+
+    fn load_completion(&self, target: &str, package: Option<&str>, slice_id: usize) -> Option<Value>;
+
+    // The targets having features in any package
+    fn feature_targets(&self) -> Vec<String>;
+
+    // The features of a target across all the packages. Items which cannot be loaded are errors
+    fn load_target_features(&self, target: &str) -> Vec<Result<Value, String>>;
+
+    // Write the items buffered by the backend, if any
+    fn flush(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// Open the SQLite database if specified, otherwise use the directory layout under the output path
+pub fn open_storage<'a, O>(options: &'a O) -> Result<Box<dyn Storage + 'a>, String>
+where
+    O: IOOptions + Send + Sync,
+{
+    match options.database_path() {
+        Some(path) => Ok(Box::new(SqliteStorage::open(path)?)),
+        None => Ok(Box::new(DirectoryStorage { options })),
+    }
+}
+
+// The default backend: one json file per item, i.e., `slices/<target>/[<package>/]<slice>.json`,
+// `traces/<target>/[<package>/]<slice>/<trace>.json` and `features/.../<trace>.fea.json`
+pub struct DirectoryStorage<'a, O: IOOptions> {
+    pub options: &'a O,
+}
+
+impl<'a, O: IOOptions> DirectoryStorage<'a, O> {
+    fn slice_file_path(&self, target: &str, package: Option<&str>, slice_id: usize) -> PathBuf {
+        match package {
+            Some(package) => self.options.slice_target_package_file_path(target, package, slice_id),
+            None => self.options.slice_target_file_path(target, slice_id),
+        }
+    }
+
+    fn trace_slice_dir(&self, target: &str, package: Option<&str>, slice_id: usize) -> PathBuf {
+        match package {
+            Some(package) => self.options.trace_target_package_slice_dir(target, package, slice_id),
+            None => self.options.trace_target_slice_dir(target, slice_id),
+        }
+    }
+
+    fn trace_file_path(&self, target: &str, package: Option<&str>, slice_id: usize, trace_id: usize) -> PathBuf {
+        self.trace_slice_dir(target, package, slice_id)
+            .join(format!("{}.json", trace_id))
+    }
+
+    fn feature_file_path(&self, target: &str, package: Option<&str>, slice_id: usize, trace_id: usize) -> PathBuf {
+        match package {
+            Some(package) => self
+                .options
+                .feature_target_package_slice_file_path(target, package, slice_id, trace_id),
+            None => self.options.feature_target_slice_file_path(target, slice_id, trace_id),
+        }
+    }
+
+    fn completion_file_path(&self, target: &str, package: Option<&str>, slice_id: usize) -> PathBuf {
+        match package {
+            Some(package) => self
+                .options
+                .completion_target_package_slice_file_path(target, package, slice_id),
+            None => self.options.completion_target_slice_file_path(target, slice_id),
+        }
+    }
+}
+
+// The `.fea.json` files under the directory, in a stable order
+fn feature_files_in_dir(dir: PathBuf) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let mut dirs = vec![dir];
+    while let Some(dir) = dirs.pop() {
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.filter_map(|entry| entry.ok()) {
+                let path = entry.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if path.to_str().map_or(false, |p| p.ends_with(".fea.json")) {
+                    files.push(path);
+                }
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+fn dump_json_with_dir(json: &Value, path: PathBuf) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|_| format!("Cannot create directory {:?}", dir))?;
+    }
+    dump_json(json, path)
+}
+
+impl<'a, O: IOOptions + Send + Sync> Storage for DirectoryStorage<'a, O> {
+    fn dump_slice(&self, target: &str, package: Option<&str>, slice_id: usize, slice: &Value) -> Result<(), String> {
+        dump_json_with_dir(slice, self.slice_file_path(target, package, slice_id))
+    }
+
+    fn load_slice(&self, target: &str, package: Option<&str>, slice_id: usize) -> Result<Value, String> {
+        load_json(&self.slice_file_path(target, package, slice_id))
+    }
+
+    fn num_slices(&self, target: &str, package: Option<&str>) -> usize {
+        let dir = match package {
+            Some(package) => self.options.slice_dir().join(target).join(package),
+            None => self.options.slice_target_dir(target),
+        };
+        match fs::read_dir(dir) {
+            Ok(dirs) => dirs.count(),
+            _ => 0,
+        }
+    }
+
+    fn init_traces(&self, target: &str, package: Option<&str>, slice_id: usize) -> Result<(), String> {
+        fs::create_dir_all(self.trace_slice_dir(target, package, slice_id))
+            .map_err(|_| "Cannot create trace function slice folder".to_string())
+    }
+
+    fn dump_trace(
+        &self,
+        target: &str,
+        package: Option<&str>,
+        slice_id: usize,
+        trace_id: usize,
+        trace: &Value,
+    ) -> Result<(), String> {
+        dump_json(trace, self.trace_file_path(target, package, slice_id, trace_id))
+    }
+
+    fn load_trace(
+        &self,
+        target: &str,
+        package: Option<&str>,
+        slice_id: usize,
+        trace_id: usize,
+    ) -> Result<Value, String> {
+        load_json(&self.trace_file_path(target, package, slice_id, trace_id))
+    }
+
+    fn trace_ids(&self, target: &str, package: Option<&str>, slice_id: usize) -> Vec<usize> {
+        match fs::read_dir(self.trace_slice_dir(target, package, slice_id)) {
+            Ok(paths) => paths
+                .map(|path| {
+                    let path = path.expect("Cannot read traces folder path").path();
+                    path.file_stem().unwrap().to_str().unwrap().parse::<usize>().unwrap()
+                })
+                .collect::<Vec<_>>(),
+            _ => vec![],
+        }
+    }
+
+    fn dump_features(
+        &self,
+        target: &str,
+        package: Option<&str>,
+        slice_id: usize,
+        trace_id: usize,
+        features: &Value,
+    ) -> Result<(), String> {
+        dump_json_with_dir(features, self.feature_file_path(target, package, slice_id, trace_id))
+    }
+
+    // The metadata is dumped to the file given by `--metadata-file` in this layout
+    fn dump_metadata(&self, _: Option<&str>, _: &Value) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn clear_traces(&self, target: &str, package: Option<&str>, slice_id: usize) -> Result<(), String> {
+        let dir = self.trace_slice_dir(target, package, slice_id);
+        if dir.exists() {
+            fs::remove_dir_all(dir).map_err(|_| "Cannot remove trace function slice folder".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn dump_completion(
+        &self,
+        target: &str,
+        package: Option<&str>,
+        slice_id: usize,
+        record: &Value,
+    ) -> Result<(), String> {
+        dump_json_with_dir(record, self.completion_file_path(target, package, slice_id))
+    }
+
+    fn load_completion(&self, target: &str, package: Option<&str>, slice_id: usize) -> Option<Value> {
+        load_json(&self.completion_file_path(target, package, slice_id)).ok()
+    }
+
+    fn feature_targets(&self) -> Vec<String> {
+        let mut targets = match fs::read_dir(self.options.feature_dir()) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().to_str().map(String::from))
+                .collect::<Vec<_>>(),
+            _ => vec![],
+        };
+        targets.sort();
+        targets
+    }
+
+    fn load_target_features(&self, target: &str) -> Vec<Result<Value, String>> {
+        feature_files_in_dir(self.options.feature_dir().join(target))
+            .into_iter()
+            .map(|path| load_json(&path).map_err(|_| format!("Can't parse {:?}", path)))
+            .collect()
+    }
+}
+
+// All the items in a single SQLite database file. The writes from all the workers are buffered and
+// written in batched transactions, which are flushed before any read and when the storage is dropped
+pub struct SqliteStorage {
+    conn: Mutex<Connection>,
+    pending: Mutex<Vec<PendingWrite>>,
+}
+
+// A statement with its parameters which is not yet written
+type PendingWrite = (&'static str, Vec<SqlValue>);
+
+// The number of writes in a transaction
+const SQLITE_BATCH_SIZE: usize = 4096;
+
+const SQLITE_SCHEMA: &str = "
+    PRAGMA journal_mode = WAL;
+    PRAGMA synchronous = NORMAL;
+    CREATE TABLE IF NOT EXISTS slices (
+        target TEXT NOT NULL, package TEXT NOT NULL, slice_id INTEGER NOT NULL, json TEXT NOT NULL,
+        PRIMARY KEY (target, package, slice_id));
+    CREATE TABLE IF NOT EXISTS traces (
+        target TEXT NOT NULL, package TEXT NOT NULL, slice_id INTEGER NOT NULL, trace_id INTEGER NOT NULL,
+        json TEXT NOT NULL, PRIMARY KEY (target, package, slice_id, trace_id));
+    CREATE TABLE IF NOT EXISTS features (
+        target TEXT NOT NULL, package TEXT NOT NULL, slice_id INTEGER NOT NULL, trace_id INTEGER NOT NULL,
+        json TEXT NOT NULL, PRIMARY KEY (target, package, slice_id, trace_id));
+    CREATE TABLE IF NOT EXISTS metadata (package TEXT NOT NULL PRIMARY KEY, json TEXT NOT NULL);
+    CREATE TABLE IF NOT EXISTS completions (
+        target TEXT NOT NULL, package TEXT NOT NULL, slice_id INTEGER NOT NULL, json TEXT NOT NULL,
+        PRIMARY KEY (target, package, slice_id));
+";
+
+// The parameters keying a slice, i.e., the target, the package and the slice id
+fn slice_key(target: &str, package: Option<&str>, slice_id: usize) -> Vec<SqlValue> {
+    vec![
+        SqlValue::Text(target.to_string()),
+        SqlValue::Text(package.unwrap_or("").to_string()),
+        SqlValue::Integer(slice_id as i64),
+    ]
+}
+
+// The parameters keying a trace and its json
+fn trace_row(target: &str, package: Option<&str>, slice_id: usize, trace_id: usize, json: &Value) -> Vec<SqlValue> {
+    let mut row = slice_key(target, package, slice_id);
+    row.push(SqlValue::Integer(trace_id as i64));
+    row.push(SqlValue::Text(json.to_string()));
+    row
+}
+
+impl SqliteStorage {
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        let conn = Connection::open(&path).map_err(|err| format!("Cannot open database {:?}: {}", path, err))?;
+        conn.execute_batch(SQLITE_SCHEMA)
+            .map_err(|err| format!("Cannot initialize database {:?}: {}", path, err))?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            pending: Mutex::new(vec![]),
+        })
+    }
+
+    // Buffer the write, and write the buffer once it is full
+    fn execute(&self, sql: &'static str, params: Vec<SqlValue>) -> Result<(), String> {
+        let mut pending = self.pending.lock().unwrap();
+        pending.push((sql, params));
+        if pending.len() >= SQLITE_BATCH_SIZE {
+            self.write(&mut pending)
+        } else {
+            Ok(())
+        }
+    }
+
+    // Write the buffered statements in order in a single transaction. The buffer stays locked,
+    // so that the writes are not reordered
+    fn write(&self, pending: &mut Vec<PendingWrite>) -> Result<(), String> {
+        if pending.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().map_err(|err| err.to_string())?;
+        for (sql, params) in pending.drain(..) {
+            let mut stmt = tx.prepare_cached(sql).map_err(|err| err.to_string())?;
+            stmt.execute(params_from_iter(params)).map_err(|err| err.to_string())?;
+        }
+        tx.commit().map_err(|err| err.to_string())
+    }
+
+    // The connection for reading, which sees all the writes buffered so far
+    fn reader(&self) -> Result<MutexGuard<Connection>, String> {
+        self.flush()?;
+        Ok(self.conn.lock().unwrap())
+    }
+
+    fn load(&self, sql: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Value, String> {
+        let conn = self.reader()?;
+        let json: Option<String> = conn
+            .query_row(sql, params, |row| row.get(0))
+            .optional()
+            .map_err(|err| err.to_string())?;
+        match json {
+            Some(json) => serde_json::from_str(&json).map_err(|x| format!("Cannot parse json: {:?}", x)),
+            None => Err("No such item in database".to_string()),
+        }
+    }
+}
+
+impl Drop for SqliteStorage {
+    fn drop(&mut self) {
+        if let Err(err) = self.flush() {
+            eprintln!("Cannot write to database: {}", err);
+        }
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn dump_slice(&self, target: &str, package: Option<&str>, slice_id: usize, slice: &Value) -> Result<(), String> {
+        let mut row = slice_key(target, package, slice_id);
+        row.push(SqlValue::Text(slice.to_string()));
+        self.execute(
+            "INSERT OR REPLACE INTO slices (target, package, slice_id, json) VALUES (?1, ?2, ?3, ?4)",
+            row,
+        )
+    }
+
+    fn load_slice(&self, target: &str, package: Option<&str>, slice_id: usize) -> Result<Value, String> {
+        self.load(
+            "SELECT json FROM slices WHERE target = ?1 AND package = ?2 AND slice_id = ?3",
+            params![target, package.unwrap_or(""), slice_id as i64],
+        )
+    }
+
+    fn num_slices(&self, target: &str, package: Option<&str>) -> usize {
+        let conn = match self.reader() {
+            Ok(conn) => conn,
+            Err(_) => return 0,
+        };
+        conn.query_row(
+            "SELECT COUNT(*) FROM slices WHERE target = ?1 AND package = ?2",
+            params![target, package.unwrap_or("")],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|n| n as usize)
+        .unwrap_or(0)
+    }
+
+    fn init_traces(&self, _: &str, _: Option<&str>, _: usize) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn dump_trace(
+        &self,
+        target: &str,
+        package: Option<&str>,
+        slice_id: usize,
+        trace_id: usize,
+        trace: &Value,
+    ) -> Result<(), String> {
+        self.execute(
+            "INSERT OR REPLACE INTO traces (target, package, slice_id, trace_id, json) VALUES (?1, ?2, ?3, ?4, ?5)",
+            trace_row(target, package, slice_id, trace_id, trace),
+        )
+    }
+
+    fn load_trace(
+        &self,
+        target: &str,
+        package: Option<&str>,
+        slice_id: usize,
+        trace_id: usize,
+    ) -> Result<Value, String> {
+        self.load(
+            "SELECT json FROM traces WHERE target = ?1 AND package = ?2 AND slice_id = ?3 AND trace_id = ?4",
+            params![target, package.unwrap_or(""), slice_id as i64, trace_id as i64],
+        )
+    }
+
+    fn trace_ids(&self, target: &str, package: Option<&str>, slice_id: usize) -> Vec<usize> {
+        let conn = match self.reader() {
+            Ok(conn) => conn,
+            Err(_) => return vec![],
+        };
+        let mut stmt =
+            match conn.prepare("SELECT trace_id FROM traces WHERE target = ?1 AND package = ?2 AND slice_id = ?3") {
+                Ok(stmt) => stmt,
+                Err(_) => return vec![],
+            };
+        let rows = stmt.query_map(params![target, package.unwrap_or(""), slice_id as i64], |row| {
+            row.get::<_, i64>(0)
+        });
+        match rows {
+            Ok(rows) => rows.filter_map(|id| id.ok()).map(|id| id as usize).collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    fn dump_features(
+        &self,
+        target: &str,
+        package: Option<&str>,
+        slice_id: usize,
+        trace_id: usize,
+        features: &Value,
+    ) -> Result<(), String> {
+        self.execute(
+            "INSERT OR REPLACE INTO features (target, package, slice_id, trace_id, json) VALUES (?1, ?2, ?3, ?4, ?5)",
+            trace_row(target, package, slice_id, trace_id, features),
+        )
+    }
+
+    fn dump_metadata(&self, package: Option<&str>, metadata: &Value) -> Result<(), String> {
+        self.execute(
+            "INSERT OR REPLACE INTO metadata (package, json) VALUES (?1, ?2)",
+            vec![
+                SqlValue::Text(package.unwrap_or("").to_string()),
+                SqlValue::Text(metadata.to_string()),
+            ],
+        )
+    }
+
+    fn clear_traces(&self, target: &str, package: Option<&str>, slice_id: usize) -> Result<(), String> {
+        self.execute(
+            "DELETE FROM traces WHERE target = ?1 AND package = ?2 AND slice_id = ?3",
+            slice_key(target, package, slice_id),
+        )
+    }
+
+    fn dump_completion(
+        &self,
+        target: &str,
+        package: Option<&str>,
+        slice_id: usize,
+        record: &Value,
+    ) -> Result<(), String> {
+        let mut row = slice_key(target, package, slice_id);
+        row.push(SqlValue::Text(record.to_string()));
+        self.execute(
+            "INSERT OR REPLACE INTO completions (target, package, slice_id, json) VALUES (?1, ?2, ?3, ?4)",
+            row,
+        )
+    }
+
+    fn load_completion(&self, target: &str, package: Option<&str>, slice_id: usize) -> Option<Value> {
+        self.load(
+            "SELECT json FROM completions WHERE target = ?1 AND package = ?2 AND slice_id = ?3",
+            params![target, package.unwrap_or(""), slice_id as i64],
+        )
+        .ok()
+    }
+
+    fn feature_targets(&self) -> Vec<String> {
+        let conn = match self.reader() {
+            Ok(conn) => conn,
+            Err(_) => return vec![],
+        };
+        let mut stmt = match conn.prepare("SELECT DISTINCT target FROM features ORDER BY target") {
+            Ok(stmt) => stmt,
+            Err(_) => return vec![],
+        };
+        let rows = stmt.query_map(params![], |row| row.get::<_, String>(0));
+        match rows {
+            Ok(rows) => rows.filter_map(|target| target.ok()).collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    fn load_target_features(&self, target: &str) -> Vec<Result<Value, String>> {
+        let conn = match self.reader() {
+            Ok(conn) => conn,
+            Err(err) => return vec![Err(err)],
+        };
+        let mut stmt =
+            match conn.prepare("SELECT json FROM features WHERE target = ?1 ORDER BY package, slice_id, trace_id") {
+                Ok(stmt) => stmt,
+                Err(err) => return vec![Err(err.to_string())],
+            };
+        let rows = stmt.query_map(params![target], |row| row.get::<_, String>(0));
+        match rows {
+            Ok(rows) => rows
+                .map(|json| {
+                    let json = json.map_err(|err| err.to_string())?;
+                    serde_json::from_str(&json).map_err(|x| format!("Cannot parse json: {:?}", x))
+                })
+                .collect(),
+            Err(err) => vec![Err(err.to_string())],
+        }
+    }
+
+    fn flush(&self) -> Result<(), String> {
+        self.write(&mut self.pending.lock().unwrap())
+    }
+}
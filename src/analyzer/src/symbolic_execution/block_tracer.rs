@@ -3,7 +3,6 @@ use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::Direction;
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::collections::HashMap;
-use std::time::SystemTime;
 
 use crate::call_graph::*;
 use crate::slicer::*;
@@ -21,7 +20,7 @@ use crate::utils;
 pub struct CompositeFunctionBlockTraces<'ctx> {
     function: Function<'ctx>,
     block_traces: Vec<Vec<Block<'ctx>>>,
-    call_instr: CallInstruction<'ctx>,
+    call_instr: CallBaseInstruction<'ctx>,
 }
 
 /// A block trace is a list of FunctionBlockTrace
@@ -32,7 +31,7 @@ pub type CompositeBlockTrace<'ctx> = Vec<CompositeFunctionBlockTraces<'ctx>>;
 pub struct FunctionBlockTrace<'ctx> {
     pub function: Function<'ctx>,
     pub block_trace: Vec<Block<'ctx>>,
-    pub call_instr: CallInstruction<'ctx>,
+    pub call_instr: CallBaseInstruction<'ctx>,
 }
 
 /// Block trace is an array of function block trace
@@ -88,11 +87,13 @@ pub struct BlockTraceIterator<'ctx> {
 }
 
 impl<'ctx> BlockTraceIterator<'ctx> {
-    pub fn from_block_trace(block_trace: BlockTrace<'ctx>, max_traces_num: usize, not_random: bool) -> Self {
-        let rng = match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
-            Ok(n) => StdRng::seed_from_u64(n.as_secs()),
-            Err(_) => StdRng::seed_from_u64(996996),
-        };
+    pub fn from_block_trace(
+        block_trace: BlockTrace<'ctx>,
+        max_traces_num: usize,
+        not_random: bool,
+        seed: u64,
+    ) -> Self {
+        let rng = StdRng::seed_from_u64(seed);
         Self {
             block_trace,
             function_id: 0,
@@ -103,7 +104,7 @@ impl<'ctx> BlockTraceIterator<'ctx> {
         }
     }
 
-    pub fn visit_call(&mut self, instr: CallInstruction<'ctx>) -> bool {
+    pub fn visit_call(&mut self, instr: CallBaseInstruction<'ctx>) -> bool {
         if self.function_id < self.block_trace.len() {
             if self.block_trace[self.function_id].call_instr == instr {
                 self.function_id += 1;
@@ -256,18 +257,19 @@ impl<'ctx> BlockGraph<'ctx> {
 }
 
 pub trait FunctionBlockGraphTrait<'ctx> {
-    fn block_graph(&self, entry: Block<'ctx>, max_traces_num: usize, not_random: bool) -> BlockGraph<'ctx>;
+    fn block_graph(&self, entry: Block<'ctx>, max_traces_num: usize, not_random: bool, seed: u64) -> BlockGraph<'ctx>;
 
     fn block_traces_to_instr(
         &self,
         instr: Instruction<'ctx>,
         max_traces_num: usize,
         not_random: bool,
+        seed: u64,
     ) -> Vec<Vec<Block<'ctx>>>;
 }
 
 impl<'ctx> FunctionBlockGraphTrait<'ctx> for Function<'ctx> {
-    fn block_graph(&self, entry: Block<'ctx>, max_traces_num: usize, not_random: bool) -> BlockGraph<'ctx> {
+    fn block_graph(&self, entry: Block<'ctx>, max_traces_num: usize, not_random: bool, seed: u64) -> BlockGraph<'ctx> {
         let mut block_id_map = HashMap::new();
         let mut graph = DiGraph::new();
         for block in self.iter_blocks() {
@@ -286,10 +288,7 @@ impl<'ctx> FunctionBlockGraphTrait<'ctx> for Function<'ctx> {
             }
         }
         let entry_id = block_id_map[&entry];
-        let rng = match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
-            Ok(n) => StdRng::seed_from_u64(n.as_secs()),
-            Err(_) => StdRng::seed_from_u64(996996),
-        };
+        let rng = StdRng::seed_from_u64(seed);
         BlockGraph {
             graph,
             block_id_map,
@@ -305,6 +304,7 @@ impl<'ctx> FunctionBlockGraphTrait<'ctx> for Function<'ctx> {
         instr: Instruction<'ctx>,
         max_traces_num: usize,
         not_random: bool,
+        seed: u64,
     ) -> Vec<Vec<Block<'ctx>>> {
         match self.first_block() {
             None => vec![vec![]],
@@ -313,7 +313,7 @@ impl<'ctx> FunctionBlockGraphTrait<'ctx> for Function<'ctx> {
                 if entry_block == instr.parent_block() {
                     vec![vec![entry_block]]
                 } else {
-                    let mut block_graph = self.block_graph(entry_block, max_traces_num, not_random);
+                    let mut block_graph = self.block_graph(entry_block, max_traces_num, not_random, seed);
                     if block_graph.block_id_map.contains_key(&instr.parent_block()) {
                         block_graph.reverse_search_blk_traces(instr.parent_block())
                     } else {
@@ -326,16 +326,21 @@ impl<'ctx> FunctionBlockGraphTrait<'ctx> for Function<'ctx> {
 }
 
 pub trait BlockTracesFromCallGraphPath<'ctx> {
-    fn block_traces(&self, max_traces_num: usize, not_random: bool) -> Vec<BlockTrace<'ctx>>;
+    fn block_traces(&self, max_traces_num: usize, not_random: bool, seed: u64) -> Vec<BlockTrace<'ctx>>;
 }
 
 impl<'ctx> BlockTracesFromCallGraphPath<'ctx> for CallGraphPath<'ctx> {
-    fn block_traces(&self, max_traces_num: usize, not_random: bool) -> Vec<BlockTrace<'ctx>> {
+    fn block_traces(&self, max_traces_num: usize, not_random: bool, seed: u64) -> Vec<BlockTrace<'ctx>> {
         let mut curr_func = self.begin;
         let mut comp_trace = vec![];
-        for (call_instr, next_func) in &self.succ {
+        for (i, (call_instr, next_func)) in self.succ.iter().enumerate() {
             // Target-oriented block traces reverse!
-            let block_traces = curr_func.block_traces_to_instr(call_instr.as_instruction(), max_traces_num, not_random);
+            let block_traces = curr_func.block_traces_to_instr(
+                call_instr.as_instruction(),
+                max_traces_num,
+                not_random,
+                seed.wrapping_add(i as u64),
+            );
             comp_trace.push(CompositeFunctionBlockTraces {
                 function: curr_func,
                 block_traces,
@@ -348,13 +353,13 @@ impl<'ctx> BlockTracesFromCallGraphPath<'ctx> for CallGraphPath<'ctx> {
 }
 
 pub trait BlockTracesFromSlice<'ctx> {
-    fn block_traces(&self, max_traces_num: usize, not_random: bool) -> Vec<BlockTrace<'ctx>>;
+    fn block_traces(&self, max_traces_num: usize, not_random: bool, seed: u64) -> Vec<BlockTrace<'ctx>>;
 }
 
 impl<'ctx> BlockTracesFromSlice<'ctx> for Slice<'ctx> {
-    fn block_traces(&self, max_traces_num: usize, not_random: bool) -> Vec<BlockTrace<'ctx>> {
+    fn block_traces(&self, max_traces_num: usize, not_random: bool, seed: u64) -> Vec<BlockTrace<'ctx>> {
         let mut traces = vec![];
-        traces.extend(self.call_chain.block_traces(max_traces_num * 2, not_random));
+        traces.extend(self.call_chain.block_traces(max_traces_num * 2, not_random, seed));
         traces
     }
 }
@@ -3,32 +3,51 @@ use std::collections::HashMap;
 use crate::semantics::rced::*;
 
 #[derive(Debug, Clone)]
-pub struct Constraint {
-    pub cond: Comparison,
-    pub branch: bool,
+pub enum Constraint {
+    // The comparison evaluates to `branch`
+    Cond { cond: Comparison, branch: bool },
+    // At least one of the comparisons holds, e.g., the case values of a switch sharing a destination
+    AnyOf(Vec<Comparison>),
 }
 
 pub type Constraints = Vec<Constraint>;
 
 pub trait ConstraintsTrait {
-    fn sat(&self, init_symbol_id: usize) -> bool;
+    fn sat(&self, init_symbol_id: usize, int_widths: &IntWidths, use_int_encoding: bool) -> bool;
 }
 
 impl ConstraintsTrait for Constraints {
-    fn sat(&self, init_symbol_id: usize) -> bool {
+    fn sat(&self, init_symbol_id: usize, int_widths: &IntWidths, use_int_encoding: bool) -> bool {
         use z3::*;
         // Note: z3 crate has its own mutex lock
         let z3_ctx = Context::new(&z3::Config::default());
         let solver = Solver::new(&z3_ctx);
         let mut symbol_map = HashMap::new();
         let mut symbol_id = init_symbol_id as u32;
-        for Constraint { cond, branch } in self.iter() {
-            match cond.into_z3_ast(&mut symbol_map, &mut symbol_id, &z3_ctx) {
-                Some(cond) => {
-                    let formula = if *branch { cond } else { cond.not() };
-                    solver.assert(&formula);
+        let mut encode = |cond: &Comparison| {
+            if use_int_encoding {
+                cond.into_z3_ast(&mut symbol_map, &mut symbol_id, &z3_ctx)
+            } else {
+                cond.into_z3_bv(int_widths, &mut symbol_map, &mut symbol_id, &z3_ctx)
+            }
+        };
+        for constraint in self.iter() {
+            match constraint {
+                Constraint::Cond { cond, branch } => match encode(cond) {
+                    Some(cond) => {
+                        let formula = if *branch { cond } else { cond.not() };
+                        solver.assert(&formula);
+                    }
+                    _ => (),
+                },
+                Constraint::AnyOf(conds) => {
+                    // The disjunction is unconstrained if any of the comparisons cannot be encoded
+                    let conds = conds.iter().map(|cond| encode(cond)).collect::<Option<Vec<_>>>();
+                    if let Some(conds) = conds {
+                        let conds = conds.iter().collect::<Vec<_>>();
+                        solver.assert(&z3::ast::Bool::or(&z3_ctx, &conds));
+                    }
                 }
-                _ => (),
             }
         }
         match solver.check() {
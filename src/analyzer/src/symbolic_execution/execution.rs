@@ -1,37 +1,61 @@
 use indicatif::*;
-use llir::values::*;
+use llir::{types::*, values::*};
 use rayon::prelude::*;
+use serde_json::json;
 use std::collections::HashMap;
-use std::fs;
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
 
 use crate::call_graph::*;
 use crate::semantics::{rced::*, *};
 use crate::slicer::*;
+use crate::storage::*;
 use crate::utils::*;
 
 use super::*;
 
-pub struct SymbolicExecutionContext<'a, O>
+// The number of integer operations followed back from a comparison for the casts feeding it
+const MAX_EXTENSION_DEPTH: usize = 5;
+
+pub struct SymbolicExecutionContext<'a, 'ctx, O>
 where
     O: SymbolicExecutionOptions,
 {
     pub options: &'a O,
+    pub storage: &'a dyn Storage,
+    // The hash of the inputs and the options, set in resume mode
+    pub resume_key: Option<String>,
+    // The source variables of the functions executed, so that each function is scanned only once
+    debug_variables: Mutex<HashMap<Function<'ctx>, Arc<DebugVariables<'ctx>>>>,
 }
 
-impl<'a, 'ctx, O> SymbolicExecutionContext<'a, O>
+impl<'a, 'ctx, O> SymbolicExecutionContext<'a, 'ctx, O>
 where
     O: SymbolicExecutionOptions,
 {
-    pub fn new(options: &'a O) -> Self {
-        Self { options }
+    pub fn new(options: &'a O, storage: &'a dyn Storage, resume_key: Option<String>) -> Self {
+        Self {
+            options,
+            storage,
+            resume_key,
+            debug_variables: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn debug_variables(&self, func: Function<'ctx>) -> Arc<DebugVariables<'ctx>> {
+        self.debug_variables
+            .lock()
+            .unwrap()
+            .entry(func)
+            .or_insert_with(|| Arc::new(debug_variables(func)))
+            .clone()
     }
 
     pub fn execute_function(
         &self,
         instr_node_id: usize,
-        instr: CallInstruction<'ctx>,
+        instr: CallBaseInstruction<'ctx>,
         func: Function<'ctx>,
         args: Vec<Rc<Value>>,
         state: &mut State<'ctx>,
@@ -44,6 +68,7 @@ where
                     instr: Some((instr_node_id, instr)),
                     memory: LocalMemory::new(),
                     arguments: args,
+                    debug_variables: self.debug_variables(func),
                 };
                 state.stack.push(stack_frame);
                 self.execute_block(block, state)
@@ -78,11 +103,13 @@ where
             match instr {
                 Some(instr) => {
                     use Instruction::*;
-                    match instr {
+                    let next_instr = match instr {
                         Return(ret) => self.transfer_ret_instr(ret, state, env),
                         Branch(br) => self.transfer_br_instr(br, state, env),
                         Switch(swi) => self.transfer_switch_instr(swi, state, env),
-                        Call(call) => self.transfer_call_instr(call, state, env),
+                        Call(call) => self.transfer_call_instr(call.into(), state, env),
+                        Invoke(invoke) => self.transfer_call_instr(invoke.into(), state, env),
+                        Resume(res) => self.transfer_resume_instr(res, state, env),
                         Store(st) => self.transfer_store_instr(st, state, env),
                         ICmp(icmp) => self.transfer_icmp_instr(icmp, state, env),
                         Load(ld) => self.transfer_load_instr(ld, state, env),
@@ -91,14 +118,69 @@ where
                         Unreachable(unr) => self.transfer_unreachable_instr(unr, state, env),
                         Binary(bin) => self.transfer_binary_instr(bin, state, env),
                         Unary(una) => self.transfer_unary_instr(una, state, env),
+                        Select(sel) => self.transfer_select_instr(sel, state, env),
+                        Freeze(frz) => self.transfer_freeze_instr(frz, state, env),
+                        AtomicRMW(rmw) => self.transfer_atomic_rmw_instr(rmw, state, env),
+                        AtomicCmpXchg(cmpxchg) => self.transfer_atomic_cmpxchg_instr(cmpxchg, state, env),
+                        ExtractValue(ext) => self.transfer_extract_value_instr(ext, state, env),
+                        Fence(fence) => self.transfer_fence_instr(fence, state, env),
+                        VAArg(va_arg) => self.transfer_va_arg_instr(va_arg, state, env),
                         _ => instr.next_instruction(),
-                    }
+                    };
+                    self.record_int_width(instr, state);
+                    next_instr
                 }
                 None => None,
             }
         }
     }
 
+    // Record the extensions of the casts feeding the operand of the comparison for the bit-vector
+    // encoding, following the integer operations up to `depth` steps. The cast yields the same value
+    // as its operand, so the extension is recorded for the comparison rather than for the value
+    fn record_extensions(&self, comparison: &Value, operand: Operand<'ctx>, depth: usize, state: &mut State<'ctx>) {
+        if depth == 0 {
+            return;
+        }
+        match operand {
+            Operand::Instruction(Instruction::Unary(una)) => {
+                let extension = match una.unary_opcode() {
+                    UnaOp::SExt => Some(Extension::Sign),
+                    UnaOp::ZExt => Some(Extension::Zero),
+                    _ => None,
+                };
+                if let Some(extension) = extension {
+                    let value = match una.op0() {
+                        Operand::Instruction(instr) => state.stack.top().memory.get(&instr).cloned(),
+                        Operand::Argument(arg) => state.stack.top().arguments.get(arg.index()).cloned(),
+                        _ => None,
+                    };
+                    if let Some(value) = value {
+                        state.int_widths.insert_extension(comparison.clone(), &value, extension);
+                    }
+                }
+                self.record_extensions(comparison, una.op0(), depth - 1, state);
+            }
+            Operand::Instruction(Instruction::Binary(bin)) => {
+                self.record_extensions(comparison, bin.op0(), depth - 1, state);
+                self.record_extensions(comparison, bin.op1(), depth - 1, state);
+            }
+            _ => {}
+        }
+    }
+
+    // Record the bit width of the integer result of the instruction for the bit-vector encoding
+    fn record_int_width(&self, instr: Instruction<'ctx>, state: &mut State<'ctx>) {
+        if let Type::Int(int_type) = Operand::Instruction(instr).get_type() {
+            // The stack is empty once the entry function returns
+            if let Some(frame) = state.stack.last() {
+                if let Some(value) = frame.memory.get(&instr) {
+                    state.int_widths.insert_value(value, int_type.width());
+                }
+            }
+        }
+    }
+
     pub fn eval_constant_value(&self, state: &mut State<'ctx>, constant: Constant<'ctx>) -> Rc<Value> {
         match constant {
             Constant::Int(i) => Rc::new(Value::Int(i.sext_value())),
@@ -138,10 +220,19 @@ where
                     state.stack.top().memory[&instr].clone()
                 } else {
                     match instr {
-                        Instruction::Alloca(_) => {
+                        Instruction::Alloca(alloca) => {
                             let alloca_id = state.new_alloca_id();
                             let value = Rc::new(Value::Alloc(alloca_id));
                             state.stack.top_mut().memory.insert(instr, value.clone());
+                            if let Some(size) = alloca_capacity(alloca) {
+                                state.capacities.push((value.clone(), size));
+                            }
+                            // Name the location after the variable declared by `llvm.dbg.declare`
+                            if let Some(var) = state.stack.top().debug_variables.get(&operand) {
+                                if let Some(name) = SourceName::variable(*var, true) {
+                                    state.source_names.insert(&value, name);
+                                }
+                            }
                             value
                         }
                         _ => Rc::new(Value::Unknown),
@@ -150,7 +241,17 @@ where
             }
             Operand::Argument(arg) => {
                 if state.stack.top().arguments.len() > arg.index() {
-                    state.stack.top().arguments[arg.index()].clone()
+                    let value = state.stack.top().arguments[arg.index()].clone();
+                    if let Type::Int(int_type) = operand.get_type() {
+                        state.int_widths.insert_value(&value, int_type.width());
+                    }
+                    // Name the argument after the parameter described by `llvm.dbg.value`
+                    if let Some(var) = state.stack.top().debug_variables.get(&operand) {
+                        if let Some(name) = SourceName::variable(*var, false) {
+                            state.source_names.insert(&value, name);
+                        }
+                    }
+                    value
                 } else {
                     Rc::new(Value::Unknown)
                 }
@@ -243,11 +344,40 @@ where
         }
     }
 
+    // The value loaded from a constant global at constant indices. Aggregates are not folded
+    pub fn load_from_constant_global(
+        &self,
+        state: &mut State<'ctx>,
+        instr: LoadInstruction<'ctx>,
+    ) -> Option<Rc<Value>> {
+        let constant = constant_at_location(instr.location())?;
+        match constant {
+            Constant::Int(_)
+            | Constant::Null(_)
+            | Constant::Function(_)
+            | Constant::Global(_)
+            | Constant::ConstExpr(_) => {
+                // Loading through a cast may reinterpret the constant
+                if constant.get_type() == instr.get_type() {
+                    Some(self.eval_constant_value(state, constant))
+                } else {
+                    None
+                }
+            }
+            _ if constant.is_null_value() => match instr.get_type() {
+                Type::Int(_) => Some(Rc::new(Value::Int(0))),
+                Type::Pointer(_) => Some(Rc::new(Value::Null)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     pub fn transfer_ret_instr(
         &self,
         instr: ReturnInstruction<'ctx>,
         state: &mut State<'ctx>,
-        _: &mut Environment<'ctx>,
+        env: &mut Environment<'ctx>,
     ) -> Option<Instruction<'ctx>> {
         // First evaluate the return operand. There might not be one
         let val = instr.op().map(|val| self.eval_operand_value(state, val));
@@ -271,7 +401,7 @@ where
                 if state.in_relevant_method {
                     state.in_relevant_method = false;
                 }
-                call_site.next_instruction()
+                self.continue_after_call(call_site, state, env)
             }
 
             // If no call site then we are in the entry function. We will end the execution
@@ -461,68 +591,151 @@ where
         let curr_blk = instr.parent_block();
         state.prev_block = Some(curr_blk);
         let cond = self.eval_operand_value(state, instr.condition().into());
-        let branches = instr
-            .cases()
+        let default_dest = instr.default_destination();
+        let mut case_values = vec![];
+        // The case values grouped by their destinations, except for the ones going to the default destination
+        let mut destinations: Vec<(Block<'ctx>, Vec<i64>)> = vec![];
+        for case in instr.cases() {
+            let value = case.case.sext_value();
+            case_values.push(value);
+            if case.destination == default_dest {
+                continue;
+            }
+            match destinations.iter_mut().find(|(dest, _)| *dest == case.destination) {
+                Some((_, values)) => values.push(value),
+                None => destinations.push((case.destination, vec![value])),
+            }
+        }
+        // The default destination is taken when none of the other destinations is
+        let excluded = destinations
             .iter()
-            .map(|case| BranchDirection {
-                from: curr_blk,
-                to: case.destination,
-            })
+            .flat_map(|(_, values)| values.iter().cloned())
             .collect::<Vec<_>>();
         let default_br = BranchDirection {
             from: curr_blk,
-            to: instr.default_destination(),
+            to: default_dest,
         };
-        let node = TraceNode {
-            instr: instr.as_instruction(),
-            semantics: Semantics::Switch { cond },
-            result: None,
-        };
-        state.trace.push(node);
 
-        if state
-            .block_trace_iter
-            .visit_block(curr_blk, instr.default_destination(), false)
-        {
-            return self.execute_block(instr.default_destination(), state);
+        if state.block_trace_iter.visit_block(curr_blk, default_dest, false) {
+            self.take_switch_case(instr, &cond, &case_values, &excluded, None, state);
+            return self.execute_block(default_dest, state);
         }
 
         // Insert branches as work if not visited
-        for bd in &branches {
-            if state.block_trace_iter.visit_block(curr_blk, bd.to, false) {
-                state.visited_branch.insert(*bd);
-                return self.execute_block(bd.to, state);
+        for (dest, values) in &destinations {
+            if state.block_trace_iter.visit_block(curr_blk, *dest, false) {
+                state.visited_branch.insert(BranchDirection {
+                    from: curr_blk,
+                    to: *dest,
+                });
+                self.take_switch_case(instr, &cond, &case_values, &excluded, Some(values), state);
+                return self.execute_block(*dest, state);
             }
         }
 
-        for bd in branches {
-            if !state.visited_branch.contains(&bd) && self.can_add_work(env.work_list.len()) {
+        for (dest, values) in &destinations {
+            let bd = BranchDirection {
+                from: curr_blk,
+                to: *dest,
+            };
+            if !state.visited_branch.contains(&bd)
+                && self.can_take_switch_case(&cond, &excluded, Some(values), env)
+                && self.can_add_work(env.work_list.len())
+            {
                 let mut br_state = state.clone();
                 br_state.visited_branch.insert(bd);
-                let br_work = Work::new(bd.to, br_state);
+                self.take_switch_case(instr, &cond, &case_values, &excluded, Some(values), &mut br_state);
+                let br_work = Work::new(*dest, br_state);
                 env.add_work(br_work);
             }
         }
 
         // Execute default branch
-        if !state.visited_branch.contains(&default_br) {
+        if !state.visited_branch.contains(&default_br) && self.can_take_switch_case(&cond, &excluded, None, env) {
             state.visited_branch.insert(default_br);
-            self.execute_block(instr.default_destination(), state)
+            self.take_switch_case(instr, &cond, &case_values, &excluded, None, state);
+            self.execute_block(default_dest, state)
         } else {
             state.finish_state = FinishState::BranchExplored;
             None
         }
     }
 
+    // Whether the destination taken by the case values (`None` for default) can be taken
+    // when the condition is a constant
+    fn can_take_switch_case(
+        &self,
+        cond: &Rc<Value>,
+        excluded: &[i64],
+        taken: Option<&[i64]>,
+        env: &Environment<'ctx>,
+    ) -> bool {
+        if env.is_rough_mode() {
+            return true;
+        }
+        match (cond.eval_constant_value(), taken) {
+            (Some(i), Some(values)) => values.contains(&i),
+            (Some(i), None) => !excluded.contains(&i),
+            _ => true,
+        }
+    }
+
+    // Record the destination taken by the case values (`None` for default) in the trace and add its
+    // constraints: `cond == value` for any of the case values, or `cond != value` for every excluded
+    // value of the cases going to the other destinations for the default.
+    fn take_switch_case(
+        &self,
+        instr: SwitchInstruction<'ctx>,
+        cond: &Rc<Value>,
+        case_values: &[i64],
+        excluded: &[i64],
+        taken: Option<&[i64]>,
+        state: &mut State<'ctx>,
+    ) {
+        let width = match instr.condition().get_type() {
+            Type::Int(int_type) => Some(int_type.width()),
+            _ => None,
+        };
+        let mut comparison = |value: i64| {
+            let comparison = Comparison::new(Predicate::EQ, cond.clone(), Rc::new(Value::Int(value)));
+            if let Some(width) = width {
+                state.int_widths.insert_comparison(comparison.as_value(), width);
+                self.record_extensions(&comparison.as_value(), instr.condition(), MAX_EXTENSION_DEPTH, state);
+            }
+            comparison
+        };
+        match taken {
+            Some(values) => {
+                let comparisons = values.iter().map(|value| comparison(*value)).collect();
+                state.add_any_constraint(comparisons);
+            }
+            None => {
+                let comparisons = excluded.iter().map(|value| comparison(*value)).collect::<Vec<_>>();
+                for comparison in comparisons {
+                    state.add_constraint(comparison, false);
+                }
+            }
+        }
+        state.trace.push(TraceNode {
+            instr: instr.as_instruction(),
+            semantics: Semantics::Switch {
+                cond: cond.clone(),
+                cases: case_values.to_vec(),
+                taken_cases: taken.map(|values| values.to_vec()).unwrap_or_default(),
+            },
+            result: None,
+        });
+    }
+
     pub fn transfer_call_instr(
         &self,
-        instr: CallInstruction<'ctx>,
+        instr: CallBaseInstruction<'ctx>,
         state: &mut State<'ctx>,
         env: &mut Environment<'ctx>,
     ) -> Option<Instruction<'ctx>> {
         // If is intrinsic call, skip the instruction
         if instr.is_intrinsic_call() {
-            instr.next_instruction()
+            self.continue_after_call(instr, state, env)
         } else {
             // Check if stepping in the function, and get the function Value and also
             // maybe function reference
@@ -540,7 +753,11 @@ where
                     if instr.is_inline_asm_call() {
                         (false, Rc::new(Value::Asm), None)
                     } else {
-                        (false, Rc::new(Value::FuncPtr), None)
+                        // Trust the resolved callee if the indirect call is the target or in the call chain
+                        match env.slice.resolved_callee(instr) {
+                            Some(func) => (false, Rc::new(Value::Func(func.simp_name())), Some(func)),
+                            None => (false, Rc::new(Value::FuncPtr), None),
+                        }
                     }
                 }
             };
@@ -606,7 +823,81 @@ where
                 for value in instr.arguments() {
                     self.replace_value_as_sym(state, value);
                 }
-                instr.next_instruction()
+                self.continue_after_call(instr, state, env)
+            }
+        }
+    }
+
+    // Continue the execution after a call site. An invoke is a terminator: we go to its normal
+    // destination (or the unwind one if the guiding block trace says so) and add the other one as a work.
+    pub fn continue_after_call(
+        &self,
+        instr: CallBaseInstruction<'ctx>,
+        state: &mut State<'ctx>,
+        env: &mut Environment<'ctx>,
+    ) -> Option<Instruction<'ctx>> {
+        match instr {
+            CallBaseInstruction::Call(call) => call.next_instruction(),
+            CallBaseInstruction::Invoke(invoke) => {
+                // Set previous block
+                let curr_blk = invoke.parent_block();
+                state.prev_block = Some(curr_blk);
+                let normal_br = BranchDirection {
+                    from: curr_blk,
+                    to: invoke.normal_destination(),
+                };
+                let unwind_br = BranchDirection {
+                    from: curr_blk,
+                    to: invoke.unwind_destination(),
+                };
+                let (br, other_br) = if state.block_trace_iter.visit_block(curr_blk, unwind_br.to, false) {
+                    (unwind_br, normal_br)
+                } else {
+                    (normal_br, unwind_br)
+                };
+
+                // Explore the other edge as its own path
+                if !state.visited_branch.contains(&other_br) && self.can_add_work(env.work_list.len()) {
+                    let mut other_state = state.clone();
+                    other_state.visited_branch.insert(other_br);
+                    env.add_work(Work::new(other_br.to, other_state));
+                }
+                state.visited_branch.insert(br);
+                self.execute_block(br.to, state)
+            }
+        }
+    }
+
+    // Propagate an in-flight exception: unwind the stack to the closest invoke and continue
+    // from its unwind destination. Unwinding out of the entry function finishes the trace, which is
+    // not dumped since it is not a normal use of the target.
+    pub fn transfer_resume_instr(
+        &self,
+        _: ResumeInstruction<'ctx>,
+        state: &mut State<'ctx>,
+        _: &mut Environment<'ctx>,
+    ) -> Option<Instruction<'ctx>> {
+        loop {
+            let stack_frame = state.stack.pop().unwrap(); // There has to be a stack on the top
+            if state.in_relevant_method {
+                state.in_relevant_method = false;
+            }
+            match stack_frame.instr {
+                Some((_, CallBaseInstruction::Invoke(invoke))) => {
+                    let curr_blk = invoke.parent_block();
+                    state.prev_block = Some(curr_blk);
+                    state.visited_branch.insert(BranchDirection {
+                        from: curr_blk,
+                        to: invoke.unwind_destination(),
+                    });
+                    return self.execute_block(invoke.unwind_destination(), state);
+                }
+                // A plain call does not catch, keep unwinding
+                Some((_, CallBaseInstruction::Call(_))) => {}
+                None => {
+                    state.finish_state = FinishState::Unwound;
+                    return None;
+                }
             }
         }
     }
@@ -638,6 +929,7 @@ where
                 state.memory.insert(loc.clone(), val.clone());
             }
         }
+        state.source_names.name_store(&val, &loc);
         let node = TraceNode {
             instr: instr.as_instruction(),
             semantics: Semantics::Store { loc, val },
@@ -654,7 +946,12 @@ where
         _: &mut Environment<'ctx>,
     ) -> Option<Instruction<'ctx>> {
         let loc = self.eval_operand_value(state, instr.location());
-        let res = self.load_from_memory(state, loc.clone());
+        // Fold the loads from constant globals, e.g., `static const` tables
+        let res = match self.load_from_constant_global(state, instr) {
+            Some(res) => res,
+            None => self.load_from_memory(state, loc.clone()),
+        };
+        state.source_names.name_load(&res, &loc);
         let node = TraceNode {
             instr: instr.as_instruction(),
             semantics: Semantics::Load { loc },
@@ -679,6 +976,12 @@ where
             op0: op0.clone(),
             op1: op1.clone(),
         });
+        // Record the bit width of the operands for the bit-vector encoding
+        if let Type::Int(int_type) = instr.op0().get_type() {
+            state.int_widths.insert_comparison((*res).clone(), int_type.width());
+            self.record_extensions(&res, instr.op0(), MAX_EXTENSION_DEPTH, state);
+            self.record_extensions(&res, instr.op1(), MAX_EXTENSION_DEPTH, state);
+        }
         let semantics = Semantics::ICmp { pred, op0, op1 };
         let node = TraceNode {
             instr: instr.as_instruction(),
@@ -724,6 +1027,7 @@ where
             loc: loc.clone(),
             indices: indices.clone(),
         });
+        state.source_names.name_gep(&res, &loc, &indices, instr);
         let node = TraceNode {
             instr: instr.as_instruction(),
             semantics: Semantics::GEP {
@@ -787,6 +1091,247 @@ where
         instr.next_instruction()
     }
 
+    pub fn transfer_select_instr(
+        &self,
+        instr: SelectInstruction<'ctx>,
+        state: &mut State<'ctx>,
+        env: &mut Environment<'ctx>,
+    ) -> Option<Instruction<'ctx>> {
+        let cond = self.eval_operand_value(state, instr.condition());
+        let true_val = self.eval_operand_value(state, instr.true_value());
+        let false_val = self.eval_operand_value(state, instr.false_value());
+        let semantics = Semantics::Select {
+            cond: cond.clone(),
+            true_val: true_val.clone(),
+            false_val: false_val.clone(),
+        };
+
+        // Directly take the selected value if the condition can be decided
+        let res = match cond.eval_constant_cond() {
+            Some(true) => true_val,
+            Some(false) => false_val,
+            None => match cond.as_comparison() {
+                // Fork the path on the symbolic condition: the current state takes the true value,
+                // and the false value is resumed as a new work right after the select
+                Some(comparison) if self.options.split_select() && !env.is_rough_mode() => {
+                    if self.can_add_work(env.work_list.len()) {
+                        if let Some(next_instr) = instr.next_instruction() {
+                            let mut false_state = state.clone();
+                            false_state.add_constraint(comparison.clone(), false);
+                            false_state.select_arms.push(false);
+                            false_state.trace.push(TraceNode {
+                                instr: instr.as_instruction(),
+                                semantics: semantics.clone(),
+                                result: Some(false_val.clone()),
+                            });
+                            false_state
+                                .stack
+                                .top_mut()
+                                .memory
+                                .insert(instr.as_instruction(), false_val);
+                            env.add_work(Work::resume_at(next_instr, false_state));
+                        }
+                    }
+                    state.add_constraint(comparison, true);
+                    state.select_arms.push(true);
+                    true_val
+                }
+                _ => Rc::new(Value::Select {
+                    cond,
+                    true_val,
+                    false_val,
+                }),
+            },
+        };
+        let node = TraceNode {
+            instr: instr.as_instruction(),
+            semantics,
+            result: Some(res.clone()),
+        };
+        state.trace.push(node);
+        state.stack.top_mut().memory.insert(instr.as_instruction(), res);
+        instr.next_instruction()
+    }
+
+    pub fn transfer_freeze_instr(
+        &self,
+        instr: FreezeInstruction<'ctx>,
+        state: &mut State<'ctx>,
+        _: &mut Environment<'ctx>,
+    ) -> Option<Instruction<'ctx>> {
+        // Undef and poison are not modeled, so freeze is the identity
+        let op0 = self.eval_operand_value(state, instr.op0());
+        state.stack.top_mut().memory.insert(instr.as_instruction(), op0);
+        instr.next_instruction()
+    }
+
+    pub fn transfer_atomic_rmw_instr(
+        &self,
+        instr: AtomicRMWInstruction<'ctx>,
+        state: &mut State<'ctx>,
+        _: &mut Environment<'ctx>,
+    ) -> Option<Instruction<'ctx>> {
+        let loc = self.eval_operand_value(state, instr.location());
+        let val = self.eval_operand_value(state, instr.value());
+
+        let old = self.atomic_old_value(state, loc.clone());
+        state.source_names.name_load(&old, &loc);
+        state.trace.push(TraceNode {
+            instr: instr.as_instruction(),
+            semantics: Semantics::Load { loc: loc.clone() },
+            result: Some(old.clone()),
+        });
+
+        // Store the result of the operation on the old value
+        let rmw_op = instr.binary_opcode();
+        let new = match (rmw_op, rmw_op.binary_opcode()) {
+            (AtomicRMWBinaryOpcode::Xchg, _) => val,
+            (_, Some(op)) => Rc::new(Value::Bin {
+                op,
+                op0: old.clone(),
+                op1: val,
+            }),
+            (_, None) => Rc::new(Value::Sym(state.new_symbol_id())),
+        };
+        state.memory.insert(loc.clone(), new.clone());
+        state.trace.push(TraceNode {
+            instr: instr.as_instruction(),
+            semantics: Semantics::Store { loc, val: new },
+            result: None,
+        });
+        state.stack.top_mut().memory.insert(instr.as_instruction(), old);
+        instr.next_instruction()
+    }
+
+    pub fn transfer_atomic_cmpxchg_instr(
+        &self,
+        instr: AtomicCmpXchgInstruction<'ctx>,
+        state: &mut State<'ctx>,
+        _: &mut Environment<'ctx>,
+    ) -> Option<Instruction<'ctx>> {
+        let loc = self.eval_operand_value(state, instr.location());
+        let cmp = self.eval_operand_value(state, instr.compare());
+        let new = self.eval_operand_value(state, instr.new_value());
+
+        let old = self.atomic_old_value(state, loc.clone());
+        state.source_names.name_load(&old, &loc);
+        state.trace.push(TraceNode {
+            instr: instr.as_instruction(),
+            semantics: Semantics::Load { loc: loc.clone() },
+            result: Some(old.clone()),
+        });
+
+        // The new value is stored only if the old value equals the compared one
+        let success = self.cmpxchg_success(state, instr, old.clone(), cmp);
+        let val = Rc::new(Value::Select {
+            cond: success,
+            true_val: new,
+            false_val: old.clone(),
+        });
+        state.memory.insert(loc.clone(), val.clone());
+        state.trace.push(TraceNode {
+            instr: instr.as_instruction(),
+            semantics: Semantics::Store { loc, val },
+            result: None,
+        });
+
+        // The `{ old, success }` pair is represented by the old value, see `transfer_extract_value_instr`
+        state.stack.top_mut().memory.insert(instr.as_instruction(), old);
+        instr.next_instruction()
+    }
+
+    // The old value may be modified by other threads, so it is always a fresh symbol. The symbol is
+    // global when the location is, as in `load_from_memory` and `transfer_store_instr`
+    fn atomic_old_value(&self, state: &mut State<'ctx>, loc: Rc<Value>) -> Rc<Value> {
+        let is_global = match &*loc {
+            Value::Glob(_) | Value::Arg(_) => true,
+            _ => matches!(*self.load_from_memory(state, loc), Value::GlobSym(_)),
+        };
+        let symbol_id = state.new_symbol_id();
+        if is_global {
+            Rc::new(Value::GlobSym(symbol_id))
+        } else {
+            Rc::new(Value::Sym(symbol_id))
+        }
+    }
+
+    fn cmpxchg_success(
+        &self,
+        state: &mut State<'ctx>,
+        instr: AtomicCmpXchgInstruction<'ctx>,
+        old: Rc<Value>,
+        cmp: Rc<Value>,
+    ) -> Rc<Value> {
+        let success = Rc::new(Value::ICmp {
+            pred: Predicate::EQ,
+            op0: old,
+            op1: cmp,
+        });
+        // Record the bit width of the operands for the bit-vector encoding
+        if let Type::Int(int_type) = instr.compare().get_type() {
+            state.int_widths.insert_comparison((*success).clone(), int_type.width());
+            self.record_extensions(&success, instr.compare(), MAX_EXTENSION_DEPTH, state);
+        }
+        success
+    }
+
+    pub fn transfer_extract_value_instr(
+        &self,
+        instr: ExtractValueInstruction<'ctx>,
+        state: &mut State<'ctx>,
+        _: &mut Environment<'ctx>,
+    ) -> Option<Instruction<'ctx>> {
+        // Only the `{ old, success }` pair of cmpxchg is modeled
+        if let Operand::Instruction(Instruction::AtomicCmpXchg(cmpxchg)) = instr.aggregate() {
+            let old = self.eval_operand_value(state, instr.aggregate());
+            let res = match instr.indices().as_slice() {
+                [0] => Some(old),
+                [1] => {
+                    // The success flag is checked like the result of an `icmp`
+                    let cmp = self.eval_operand_value(state, cmpxchg.compare());
+                    let success = self.cmpxchg_success(state, cmpxchg, old.clone(), cmp.clone());
+                    state.trace.push(TraceNode {
+                        instr: instr.as_instruction(),
+                        semantics: Semantics::ICmp {
+                            pred: Predicate::EQ,
+                            op0: old,
+                            op1: cmp,
+                        },
+                        result: Some(success.clone()),
+                    });
+                    Some(success)
+                }
+                _ => None,
+            };
+            if let Some(res) = res {
+                state.stack.top_mut().memory.insert(instr.as_instruction(), res);
+            }
+        }
+        instr.next_instruction()
+    }
+
+    pub fn transfer_fence_instr(
+        &self,
+        instr: FenceInstruction<'ctx>,
+        _: &mut State<'ctx>,
+        _: &mut Environment<'ctx>,
+    ) -> Option<Instruction<'ctx>> {
+        // Paths are explored in a single thread, so there is nothing to order
+        instr.next_instruction()
+    }
+
+    pub fn transfer_va_arg_instr(
+        &self,
+        instr: VAArgInstruction<'ctx>,
+        state: &mut State<'ctx>,
+        _: &mut Environment<'ctx>,
+    ) -> Option<Instruction<'ctx>> {
+        // The variable arguments are not tracked, so each one is a fresh symbol
+        let res = Rc::new(Value::Sym(state.new_symbol_id()));
+        state.stack.top_mut().memory.insert(instr.as_instruction(), res);
+        instr.next_instruction()
+    }
+
     pub fn transfer_unreachable_instr(
         &self,
         _: UnreachableInstruction<'ctx>,
@@ -822,29 +1367,39 @@ where
                 match state.target_node {
                     Some(target_id) => {
                         // Generate the trace for output
-                        let trace = TraceWithTarget::new(state.trace, target_id);
+                        let trace = TraceWithTarget::new(state.trace, target_id, state.source_names, state.capacities);
 
                         // Check block trace duplication
                         let block_trace = trace.block_trace();
-                        if env.is_rough_mode() || !env.has_duplicate(&block_trace) {
-                            // Add block trace into environment
-                            env.add_block_trace(block_trace);
-
+                        if env.is_rough_mode() || !env.has_duplicate(&block_trace, &state.select_arms) {
                             // Check path satisfaction
-                            if env.is_rough_mode() || state.constraints.sat(state.symbol_id) {
+                            if env.is_rough_mode()
+                                || state.constraints.sat(
+                                    state.symbol_id,
+                                    &state.int_widths,
+                                    self.options.use_int_encoding(),
+                                )
+                            {
+                                // Add block trace into environment only when the path is feasible, so that an
+                                // infeasible path does not hide a feasible one through the same blocks
+                                env.add_block_trace(block_trace, state.select_arms);
+
                                 // Need store
                                 let trace_id = metadata.proper_trace_count;
-                                let path = self.options.trace_target_slice_file_path(
-                                    env.slice.target_function_name().as_str(),
-                                    slice_id,
-                                    trace_id,
-                                );
 
                                 // Dump the json
                                 let json_value = trace.to_json();
                                 match json_value {
                                     Ok(json_value) => {
-                                        dump_json(&json_value, path).expect("Cannot dump json");
+                                        self.storage
+                                            .dump_trace(
+                                                env.slice.target_function_name().as_str(),
+                                                self.options.basename_of_bc_file(),
+                                                slice_id,
+                                                trace_id,
+                                                &json_value,
+                                            )
+                                            .expect("Cannot dump json");
                                         // Increase the count in metadata
                                         metadata.incr_proper();
                                     }
@@ -866,12 +1421,23 @@ where
             FinishState::BranchExplored => metadata.incr_branch_explored(),
             FinishState::ExceedingMaxTraceLength => metadata.incr_exceeding_length(),
             FinishState::Unreachable => metadata.incr_unreachable(),
+            FinishState::Unwound => metadata.incr_unwound(),
             FinishState::Timeout => metadata.incr_timeout(),
         }
     }
 
     pub fn execute_block_state(&self, block: Block<'ctx>, state: &mut State<'ctx>, env: &mut Environment<'ctx>) {
-        let mut curr_instr = self.execute_block(block, state);
+        let curr_instr = self.execute_block(block, state);
+        self.execute_instr_state(curr_instr, state, env);
+    }
+
+    pub fn execute_instr_state(
+        &self,
+        instr: Option<Instruction<'ctx>>,
+        state: &mut State<'ctx>,
+        env: &mut Environment<'ctx>,
+    ) {
+        let mut curr_instr = instr;
         while curr_instr.is_some() {
             curr_instr = self.execute_instr(curr_instr, state, env);
         }
@@ -879,17 +1445,30 @@ where
 
     pub fn execute_slice(&self, slice: Slice<'ctx>, slice_id: usize) -> MetaData {
         let mut metadata = MetaData::new();
-        let mut env = Environment::new(&slice, self.options.is_rough());
+        // Derive the seed of this slice from stable identifiers, so that the exploration is
+        // reproducible regardless of the execution order of slices
+        let seed = derive_seed(
+            self.options.seed().unwrap_or_else(time_seed),
+            slice.target_function_name().as_str(),
+            slice_id,
+        );
+        let mut env = Environment::new(&slice, self.options.is_rough(), seed);
 
-        let block_traces = slice.block_traces(self.options.max_trace_per_slice(), self.options.not_random_scheduling());
+        let block_traces = slice.block_traces(
+            self.options.max_trace_per_slice(),
+            self.options.not_random_scheduling(),
+            seed,
+        );
 
         // Init works according to guiding block traces
-        for block_trace in &block_traces {
+        for (i, block_trace) in block_traces.iter().enumerate() {
             let work = Work::entry_with_block_trace(
                 &slice,
                 block_trace.to_vec(),
                 self.options.max_trace_per_slice(),
                 self.options.not_random_scheduling(),
+                seed.wrapping_add(i as u64),
+                self.debug_variables(slice.entry),
             );
             env.add_work(work);
         }
@@ -903,7 +1482,10 @@ where
             work.state.start_time = SystemTime::now();
 
             // Start the execution by iterating through instructions
-            self.execute_block_state(work.block, &mut work.state, &mut env);
+            match work.resume_instr {
+                Some(instr) => self.execute_instr_state(Some(instr), &mut work.state, &mut env),
+                None => self.execute_block_state(work.block, &mut work.state, &mut env),
+            }
 
             // Finish the instruction and settle down the states
             self.finish_execution(work.state, slice_id, &mut metadata, &mut env);
@@ -912,7 +1494,14 @@ where
             if metadata.proper_trace_count == 0
                 && (metadata.explored_trace_count == self.options.max_explored_trace_per_slice() - 1
                     || !env.has_work()) {
-                let rough_work = Work::entry_with_block_trace(&slice, block_traces[0].clone(), 0, false);
+                let rough_work = Work::entry_with_block_trace(
+                    &slice,
+                    block_traces[0].clone(),
+                    0,
+                    false,
+                    seed,
+                    self.debug_variables(slice.entry),
+                );
                 env.add_work(rough_work);
                 env.change_to_rough();
             }
@@ -921,8 +1510,55 @@ where
     }
 
     fn initialize_traces_function_slice_folder(&self, func_name: &String, slice_id: usize) -> Result<(), String> {
-        let path = self.options.trace_target_slice_dir(func_name.as_str(), slice_id);
-        fs::create_dir_all(path).map_err(|_| "Cannot create trace function slice folder".to_string())
+        self.storage
+            .init_traces(func_name.as_str(), self.options.basename_of_bc_file(), slice_id)
+    }
+
+    // The metadata of a completed slice, if its record is made with the same resume key
+    fn load_completed_slice(&self, target_name: &String, slice_id: usize) -> Option<MetaData> {
+        let key = self.resume_key.as_ref()?;
+        let record = self
+            .storage
+            .load_completion(target_name, self.options.basename_of_bc_file(), slice_id)?;
+        if record["key"].as_str() == Some(key.as_str()) {
+            MetaData::from_json(&record["metadata"])
+        } else {
+            None
+        }
+    }
+
+    fn execute_or_resume_slice(&self, target_name: &String, slice: Slice<'ctx>, slice_id: usize) -> MetaData {
+        if let Some(metadata) = self.load_completed_slice(target_name, slice_id) {
+            return metadata;
+        }
+        let package = self.options.basename_of_bc_file();
+        // Only mark the slice as completed if the stale traces are surely removed, otherwise
+        // the slice is executed again by the next run
+        let mut cleared = true;
+        if self.resume_key.is_some() {
+            // Remove the traces left by an unfinished or outdated execution
+            if let Err(err) = self.storage.clear_traces(target_name, package, slice_id) {
+                eprintln!("Cannot clear the traces of {} slice {}: {}", target_name, slice_id, err);
+                cleared = false;
+            }
+        }
+        self.initialize_traces_function_slice_folder(target_name, slice_id)
+            .unwrap();
+        let metadata = if slice.instr.debug_loc_string() != "" {
+            self.execute_slice(slice, slice_id)
+        } else {
+            MetaData::new()
+        };
+        if let (Some(key), true) = (&self.resume_key, cleared) {
+            let record = json!({ "key": key, "metadata": metadata.to_json() });
+            if let Err(err) = self.storage.dump_completion(target_name, package, slice_id, &record) {
+                eprintln!(
+                    "Cannot record the completion of {} slice {}: {}",
+                    target_name, slice_id, err
+                );
+            }
+        }
+        metadata
     }
 
     pub fn execute_target_slices(
@@ -944,14 +1580,7 @@ where
             slices.into_iter().progress_with(pb).enumerate().fold(
                 MetaData::new(),
                 |meta: MetaData, (id, slice): (usize, Slice<'ctx>)| {
-                    let slice_id = slice_id_offset + id;
-                    self.initialize_traces_function_slice_folder(target_name, slice_id)
-                        .unwrap();
-                    if slice.instr.debug_loc_string() != "" {
-                        meta.combine(self.execute_slice(slice, slice_id))
-                    } else {
-                        meta
-                    }
+                    meta.combine(self.execute_or_resume_slice(target_name, slice, slice_id_offset + id))
                 },
             )
         } else {
@@ -961,14 +1590,7 @@ where
                 .fold(
                     || MetaData::new(),
                     |meta: MetaData, (id, slice): (usize, Slice<'ctx>)| {
-                        let slice_id = slice_id_offset + id;
-                        self.initialize_traces_function_slice_folder(target_name, slice_id)
-                            .unwrap();
-                        if slice.instr.debug_loc_string() != "" {
-                            meta.combine(self.execute_slice(slice, slice_id))
-                        } else {
-                            meta
-                        }
+                        meta.combine(self.execute_or_resume_slice(target_name, slice, slice_id_offset + id))
                     },
                 )
                 .progress_with(pb)
@@ -1011,3 +1633,15 @@ where
         }
     }
 }
+
+// The size in bytes of the buffer allocated by the alloca, when the number of elements is a constant
+fn alloca_capacity<'ctx>(alloca: AllocaInstruction<'ctx>) -> Option<u64> {
+    let num_elements = match alloca.num_elements() {
+        Operand::Constant(Constant::Int(i)) => i.zext_value(),
+        _ => return None,
+    };
+    let data_layout = alloca.parent_function().parent_module().data_layout();
+    data_layout
+        .abi_size_of(&alloca.get_element_type())
+        .map(|size| size * num_elements)
+}
@@ -1,7 +1,9 @@
 use llir::values::*;
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use std::sync::Arc;
 
+use super::source_names::*;
 use crate::semantics::rced::*;
 
 pub type Memory = HashMap<Rc<Value>, Rc<Value>>;
@@ -10,13 +12,14 @@ pub type LocalMemory<'ctx> = HashMap<Instruction<'ctx>, Rc<Value>>;
 #[derive(Clone, Debug)]
 pub struct StackFrame<'ctx> {
     pub function: Function<'ctx>,
-    pub instr: Option<(usize, CallInstruction<'ctx>)>,
+    pub instr: Option<(usize, CallBaseInstruction<'ctx>)>,
     pub memory: LocalMemory<'ctx>,
     pub arguments: Vec<Rc<Value>>,
+    pub debug_variables: Arc<DebugVariables<'ctx>>,
 }
 
 impl<'ctx> StackFrame<'ctx> {
-    pub fn entry(function: Function<'ctx>) -> Self {
+    pub fn entry(function: Function<'ctx>, debug_variables: Arc<DebugVariables<'ctx>>) -> Self {
         Self {
             function,
             instr: None,
@@ -24,6 +27,7 @@ impl<'ctx> StackFrame<'ctx> {
             arguments: (0..function.num_arguments())
                 .map(|i| Rc::new(Value::Arg(i as usize)))
                 .collect(),
+            debug_variables,
         }
     }
 }
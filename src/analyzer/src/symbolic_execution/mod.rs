@@ -3,6 +3,7 @@ mod constraints;
 mod execution;
 mod memory;
 mod options;
+mod source_names;
 mod state;
 mod trace;
 mod work_env;
@@ -12,6 +13,7 @@ pub use constraints::*;
 pub use execution::*;
 pub use memory::*;
 pub use options::*;
+pub use source_names::*;
 pub use state::*;
 pub use trace::*;
 pub use work_env::*;
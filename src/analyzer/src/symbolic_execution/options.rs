@@ -16,4 +16,10 @@ pub trait SymbolicExecutionOptions: GeneralOptions + IOOptions + Send + Sync {
     fn is_rough(&self) -> bool;
 
     fn not_random_scheduling(&self) -> bool;
+
+    fn split_select(&self) -> bool;
+
+    fn use_int_encoding(&self) -> bool;
+
+    fn seed(&self) -> Option<u64>;
 }
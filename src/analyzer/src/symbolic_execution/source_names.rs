@@ -0,0 +1,298 @@
+use llir::{types::*, values::*};
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use super::trace::*;
+use crate::semantics::{rced::*, *};
+
+// The source variables of a function, keyed by the values described in `llvm.dbg.declare`/`llvm.dbg.value`
+pub type DebugVariables<'ctx> = HashMap<Operand<'ctx>, DILocalVariable<'ctx>>;
+
+pub fn debug_variables<'ctx>(function: Function<'ctx>) -> DebugVariables<'ctx> {
+    function.debug_variables().into_iter().collect()
+}
+
+// The source expression of a value, e.g., `req->len`, built from the debug info
+#[derive(Clone, Debug)]
+pub struct SourceName<'ctx> {
+    pub expr: String,
+    // Whether the value is the address of `expr`, i.e., a location
+    pub is_addr: bool,
+    // The declared type of `expr`
+    pub ty: Option<DIType<'ctx>>,
+}
+
+impl<'ctx> SourceName<'ctx> {
+    pub fn variable(var: DILocalVariable<'ctx>, is_addr: bool) -> Option<Self> {
+        var.name().map(|expr| Self {
+            expr,
+            is_addr,
+            ty: var.ty(),
+        })
+    }
+}
+
+impl<'ctx> std::fmt::Display for SourceName<'ctx> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if !self.is_addr {
+            write!(f, "{}", self.expr)
+        } else if let Some(pointer) = self.expr.strip_prefix('*') {
+            write!(f, "{}", pointer)
+        } else {
+            write!(f, "&{}", self.expr)
+        }
+    }
+}
+
+// The source names of the symbolic values along a path. The first name given to a value is kept
+#[derive(Clone, Debug, Default)]
+pub struct SourceNames<'ctx> {
+    names: HashMap<Rc<Value>, SourceName<'ctx>>,
+}
+
+impl<'ctx> SourceNames<'ctx> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, value: &Value) -> Option<SourceName<'ctx>> {
+        match value {
+            Value::Glob(name) => Some(SourceName {
+                expr: name.clone(),
+                is_addr: true,
+                ty: None,
+            }),
+            _ => self.names.get(value).cloned(),
+        }
+    }
+
+    pub fn insert(&mut self, value: &Rc<Value>, name: SourceName<'ctx>) {
+        if is_nameable(value) && !self.names.contains_key(value) {
+            self.names.insert(value.clone(), name);
+        }
+    }
+
+    // `res = *loc`
+    pub fn name_load(&mut self, res: &Rc<Value>, loc: &Value) {
+        if let Some(loc) = self.get(loc) {
+            let name = if loc.is_addr {
+                SourceName {
+                    expr: loc.expr,
+                    is_addr: false,
+                    ty: loc.ty,
+                }
+            } else {
+                SourceName {
+                    expr: format!("*{}", loc.expr),
+                    is_addr: false,
+                    ty: loc.ty.and_then(pointee_type),
+                }
+            };
+            self.insert(res, name);
+        }
+    }
+
+    // `*loc = val`, so that the value is named after the variable it is assigned to
+    pub fn name_store(&mut self, val: &Rc<Value>, loc: &Value) {
+        if let Some(loc) = self.get(loc) {
+            // A location stored is not the variable itself, e.g., `p = &x`
+            let is_location = matches!(**val, Value::Alloc(_) | Value::GEP { .. });
+            if loc.is_addr && !is_location {
+                self.insert(
+                    val,
+                    SourceName {
+                        expr: loc.expr,
+                        is_addr: false,
+                        ty: loc.ty,
+                    },
+                );
+            }
+        }
+    }
+
+    // `res = &loc[indices[0]].field[...]`, where the struct fields are named by the debug info
+    pub fn name_gep(
+        &mut self,
+        res: &Rc<Value>,
+        loc: &Value,
+        indices: &[Rc<Value>],
+        gep: GetElementPtrInstruction<'ctx>,
+    ) {
+        if let Some(name) = self.gep_name(loc, indices, gep) {
+            self.insert(res, name);
+        }
+    }
+
+    fn gep_name(
+        &self,
+        loc: &Value,
+        indices: &[Rc<Value>],
+        gep: GetElementPtrInstruction<'ctx>,
+    ) -> Option<SourceName<'ctx>> {
+        let base = self.get(loc)?;
+        let (first, rest) = indices.split_first()?;
+
+        // The IR type indexed into, for matching the struct fields with the members by their offsets
+        let mut ir_ty = match gep.location().get_type() {
+            Type::Pointer(ptr_ty) => ptr_ty.element_type(),
+            _ => return None,
+        };
+        let data_layout = gep.parent_function().parent_module().data_layout();
+
+        // The first index steps over the pointer
+        let (mut expr, mut ty) = match (base.is_addr, &**first) {
+            (true, Value::Int(0)) => (base.expr, base.ty),
+            (true, _) => return None,
+            (false, Value::Int(0)) => (format!("*{}", base.expr), base.ty.and_then(pointee_type)),
+            (false, index) => (
+                format!("{}[{}]", base.expr, self.index_expr(index)),
+                base.ty.and_then(pointee_type),
+            ),
+        };
+
+        // The rest index into the structs and arrays
+        for index in rest {
+            let agg = ty?.strip_typedefs()?;
+            match (agg.kind(), ir_ty) {
+                (DITypeKind::Struct, Type::Struct(struct_ty)) => {
+                    let i = match **index {
+                        Value::Int(i) if i >= 0 => i as usize,
+                        _ => return None,
+                    };
+                    // The fields of the IR struct do not follow the members one by one, e.g., with padding
+                    // or bit fields, so the member is the only one starting at the offset of the field
+                    let offset = data_layout.offset_of_element(&struct_ty, i)? * 8;
+                    let mut members = agg.members().into_iter().filter(|m| m.offset_in_bits() == offset);
+                    let member = match (members.next(), members.next()) {
+                        (Some(member), None) => member,
+                        _ => return None,
+                    };
+                    ir_ty = struct_ty.element_type(i)?;
+                    let field = member.name().unwrap_or_default();
+                    expr = match expr.strip_prefix('*') {
+                        Some(pointer) => format!("{}->{}", pointer, field),
+                        None => format!("{}.{}", expr, field),
+                    };
+                    ty = member.base_type();
+                }
+                (DITypeKind::Array, Type::Array(array_ty)) => {
+                    expr = format!("{}[{}]", expr, self.index_expr(index));
+                    ty = agg.base_type();
+                    ir_ty = array_ty.element_type();
+                }
+                _ => return None,
+            }
+        }
+        Some(SourceName {
+            expr,
+            is_addr: true,
+            ty,
+        })
+    }
+
+    fn index_expr(&self, index: &Value) -> String {
+        self.expr(index).unwrap_or("?".to_string())
+    }
+
+    // The source expression of a value. Constants, calls and operations are rendered from their operands
+    pub fn expr(&self, value: &Value) -> Option<String> {
+        if let Some(name) = self.get(value) {
+            return Some(name.to_string());
+        }
+        match value {
+            Value::Int(i) => Some(i.to_string()),
+            Value::Null => Some("NULL".to_string()),
+            Value::Func(func) => Some(func.clone()),
+            Value::Call { func, args, .. } => {
+                let func = self.expr(func)?;
+                let args = args.iter().map(|arg| self.index_expr(arg)).collect::<Vec<_>>();
+                Some(format!("{}({})", func, args.join(", ")))
+            }
+            Value::Bin { op, op0, op1 } => Some(format!(
+                "{} {} {}",
+                self.operand_expr(op0)?,
+                bin_op_str(*op),
+                self.operand_expr(op1)?
+            )),
+            Value::ICmp { pred, op0, op1 } => Some(format!(
+                "{} {} {}",
+                self.operand_expr(op0)?,
+                pred_str(*pred),
+                self.operand_expr(op1)?
+            )),
+            _ => None,
+        }
+    }
+
+    fn operand_expr(&self, value: &Value) -> Option<String> {
+        self.expr(value).map(|expr| {
+            if expr.contains(' ') {
+                format!("({})", expr)
+            } else {
+                expr
+            }
+        })
+    }
+}
+
+impl<'ctx> TraceNode<'ctx> {
+    // The source expressions of the result and the operands, keyed as `res` and the operand names
+    pub fn source_exprs(&self, names: &SourceNames<'ctx>) -> BTreeMap<String, String> {
+        let mut exprs = BTreeMap::new();
+        if let Some(res) = &self.result {
+            if let Some(expr) = names.expr(res) {
+                exprs.insert("res".to_string(), expr);
+            }
+        }
+        for (key, op) in self.semantics.operands() {
+            if let Some(expr) = names.expr(op) {
+                exprs.insert(key, expr);
+            }
+        }
+        exprs
+    }
+}
+
+// Constants and locations are rendered on the fly
+fn is_nameable(value: &Value) -> bool {
+    match value {
+        Value::Arg(_) | Value::Sym(_) | Value::ConstSym(_) | Value::GlobSym(_) => true,
+        Value::Call { .. } | Value::Bin { .. } | Value::Select { .. } => true,
+        Value::Alloc(_) | Value::GEP { .. } => true,
+        _ => false,
+    }
+}
+
+fn pointee_type(ty: DIType) -> Option<DIType> {
+    let ty = ty.strip_typedefs()?;
+    match ty.kind() {
+        DITypeKind::Pointer | DITypeKind::Array => ty.base_type(),
+        _ => None,
+    }
+}
+
+fn bin_op_str(op: BinOp) -> &'static str {
+    match op {
+        BinOp::Add | BinOp::FAdd => "+",
+        BinOp::Sub | BinOp::FSub => "-",
+        BinOp::Mul | BinOp::FMul => "*",
+        BinOp::UDiv | BinOp::SDiv | BinOp::FDiv => "/",
+        BinOp::URem | BinOp::SRem | BinOp::FRem => "%",
+        BinOp::Shl => "<<",
+        BinOp::LShr | BinOp::AShr => ">>",
+        BinOp::And => "&",
+        BinOp::Or => "|",
+        BinOp::Xor => "^",
+    }
+}
+
+fn pred_str(pred: Predicate) -> &'static str {
+    match pred {
+        Predicate::EQ => "==",
+        Predicate::NE => "!=",
+        Predicate::SGE | Predicate::UGE => ">=",
+        Predicate::SGT | Predicate::UGT => ">",
+        Predicate::SLE | Predicate::ULE => "<=",
+        Predicate::SLT | Predicate::ULT => "<",
+    }
+}
@@ -1,9 +1,11 @@
 use llir::values::*;
+use std::sync::Arc;
 use std::time::SystemTime;
 
 use super::block_tracer::*;
 use super::constraints::*;
 use super::memory::*;
+use super::source_names::*;
 use super::trace::*;
 use crate::semantics::rced::*;
 use crate::slicer::*;
@@ -14,6 +16,8 @@ pub enum FinishState {
     BranchExplored,
     ExceedingMaxTraceLength,
     Unreachable,
+    // An exception is propagated out of the entry function, so the trace has no `Ret`
+    Unwound,
     Timeout,
 }
 
@@ -28,6 +32,11 @@ pub struct State<'ctx> {
     pub prev_block: Option<Block<'ctx>>,
     pub finish_state: FinishState,
     pub constraints: Constraints,
+    // The arms taken on the selects forked on the path, `true` for the true value
+    pub select_arms: Vec<bool>,
+    pub int_widths: IntWidths,
+    pub source_names: SourceNames<'ctx>,
+    pub capacities: Capacities,
     pub start_time: SystemTime,
 
     // Identifiers
@@ -47,12 +56,14 @@ impl<'ctx> State<'ctx> {
         block_trace: BlockTrace<'ctx>,
         max_traces_num: usize,
         not_random: bool,
+        seed: u64,
+        debug_variables: Arc<DebugVariables<'ctx>>,
     ) -> Self {
         Self {
-            stack: vec![StackFrame::entry(slice.entry)],
+            stack: vec![StackFrame::entry(slice.entry, debug_variables)],
             memory: Memory::new(),
             block_trace_iter: BlockTraceIterator::from_block_trace(
-                block_trace, max_traces_num, not_random
+                block_trace, max_traces_num, not_random, seed
             ),
             visited_branch: VisitedBranch::new(),
             trace: Vec::new(),
@@ -60,6 +71,10 @@ impl<'ctx> State<'ctx> {
             prev_block: None,
             finish_state: FinishState::ProperlyReturned,
             constraints: Vec::new(),
+            select_arms: Vec::new(),
+            int_widths: IntWidths::new(),
+            source_names: SourceNames::new(),
+            capacities: Capacities::new(),
             start_time: SystemTime::now(),
             alloca_id: 0,
             symbol_id: 0,
@@ -81,7 +96,16 @@ impl<'ctx> State<'ctx> {
     }
 
     pub fn add_constraint(&mut self, cond: Comparison, branch: bool) {
-        self.constraints.push(Constraint { cond, branch });
+        self.constraints.push(Constraint::Cond { cond, branch });
+    }
+
+    // Add the constraint that at least one of the comparisons holds
+    pub fn add_any_constraint(&mut self, conds: Vec<Comparison>) {
+        if conds.len() == 1 {
+            self.add_constraint(conds.into_iter().next().unwrap(), true);
+        } else if !conds.is_empty() {
+            self.constraints.push(Constraint::AnyOf(conds));
+        }
     }
 
     pub fn has_timeouted(&mut self, max_time: usize) -> bool {
@@ -3,6 +3,7 @@ use serde_json::json;
 use std::rc::Rc;
 use std::time::SystemTime;
 
+use super::source_names::*;
 use crate::semantics::rced::*;
 
 #[derive(Clone, Debug)]
@@ -14,14 +15,30 @@ pub struct TraceNode<'ctx> {
 
 pub type Trace<'ctx> = Vec<TraceNode<'ctx>>;
 
+// The locations and sizes in bytes of the stack buffers allocated along a path, in the order of allocation
+// so that the traces dumped are the same across runs
+pub type Capacities = Vec<(Rc<Value>, u64)>;
+
 pub struct TraceWithTarget<'ctx> {
     pub trace: Trace<'ctx>,
     pub target_index: usize,
+    pub source_names: SourceNames<'ctx>,
+    pub capacities: Capacities,
 }
 
 impl<'ctx> TraceWithTarget<'ctx> {
-    pub fn new(trace: Trace<'ctx>, target_index: usize) -> Self {
-        Self { trace, target_index }
+    pub fn new(
+        trace: Trace<'ctx>,
+        target_index: usize,
+        source_names: SourceNames<'ctx>,
+        capacities: Capacities,
+    ) -> Self {
+        Self {
+            trace,
+            target_index,
+            source_names,
+            capacities,
+        }
     }
 
     pub fn target(&self) -> &TraceNode<'ctx> {
@@ -32,7 +49,7 @@ impl<'ctx> TraceWithTarget<'ctx> {
         let mut timeout = false;
         let start_time = SystemTime::now();
         let mut instrs_info: Vec<serde_json::Value> = Vec::new();
-        for node in self.trace.iter() {
+        for (node, depth) in self.trace.iter().zip(self.call_depths()) {
             match start_time.elapsed() {
                 Ok(elapsed) => {
                     if elapsed.as_secs() as usize >= 3 {
@@ -44,19 +61,52 @@ impl<'ctx> TraceWithTarget<'ctx> {
             if timeout {
                 return Err("Cannot dump json".to_string());
             }
-            instrs_info.push(json!({
+            let mut instr_info = json!({
                 "loc": node.instr.debug_loc_string(),
                 "sem": node.semantics,
                 "res": node.result
-            }));
+            });
+            let src = node.source_exprs(&self.source_names);
+            if !src.is_empty() {
+                instr_info["src"] = json!(src);
+            }
+            if depth > 0 {
+                instr_info["depth"] = json!(depth);
+            }
+            instrs_info.push(instr_info);
         }
-        let json_value = json!({
+        let mut json_value = json!({
             "instrs": instrs_info,
             "target": self.target_index,
         });
+        if !self.capacities.is_empty() {
+            let capacities: Vec<_> = self
+                .capacities
+                .iter()
+                .map(|(loc, size)| json!({ "loc": loc, "size": size }))
+                .collect();
+            json_value["capacities"] = json!(capacities);
+        }
         Ok(json_value)
     }
 
+    // The depth of the call stack at each node, which is 0 in the entry function. The functions stepped in
+    // are never recursive, so seeing a function on the stack again means returning (or unwinding) to it
+    pub fn call_depths(&self) -> Vec<usize> {
+        let mut stack: Vec<Function<'ctx>> = vec![];
+        self.trace
+            .iter()
+            .map(|node| {
+                let func = node.instr.parent_function();
+                match stack.iter().position(|f| *f == func) {
+                    Some(i) => stack.truncate(i + 1),
+                    None => stack.push(func),
+                }
+                stack.len() - 1
+            })
+            .collect()
+    }
+
     pub fn block_trace(&self) -> Vec<Block<'ctx>> {
         let mut bt = vec![];
         for node in &self.trace {
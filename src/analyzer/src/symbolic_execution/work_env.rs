@@ -1,6 +1,6 @@
 use llir::values::*;
 use rand::{rngs::StdRng, Rng, SeedableRng};
-use std::time::SystemTime;
+use std::sync::Arc;
 
 use crate::slicer::*;
 use crate::symbolic_execution::*;
@@ -9,6 +9,8 @@ use crate::symbolic_execution::*;
 pub struct Work<'ctx> {
     pub block: Block<'ctx>,
     pub state: State<'ctx>,
+    // The instruction to resume from when the work is forked in the middle of a block
+    pub resume_instr: Option<Instruction<'ctx>>,
 }
 
 impl<'ctx> Work<'ctx> {
@@ -17,38 +19,54 @@ impl<'ctx> Work<'ctx> {
         block_trace: BlockTrace<'ctx>,
         max_traces_num: usize,
         not_random: bool,
+        seed: u64,
+        debug_variables: Arc<DebugVariables<'ctx>>,
     ) -> Self {
         let block = slice.entry.first_block().unwrap();
-        let state = State::from_block_trace(slice, block_trace, max_traces_num, not_random);
-        Self { block, state }
+        let state = State::from_block_trace(slice, block_trace, max_traces_num, not_random, seed, debug_variables);
+        Self {
+            block,
+            state,
+            resume_instr: None,
+        }
     }
 
     pub fn new(block: Block<'ctx>, state: State<'ctx>) -> Self {
-        Self { block, state }
+        Self {
+            block,
+            state,
+            resume_instr: None,
+        }
+    }
+
+    pub fn resume_at(instr: Instruction<'ctx>, state: State<'ctx>) -> Self {
+        Self {
+            block: instr.parent_block(),
+            state,
+            resume_instr: Some(instr),
+        }
     }
 }
 
 pub struct Environment<'ctx> {
     pub slice: Slice<'ctx>,
     pub work_list: Vec<Work<'ctx>>,
-    pub block_traces: Vec<Vec<Block<'ctx>>>,
+    // The block traces of the feasible paths, with the arms taken on the selects forked
+    pub block_traces: Vec<(Vec<Block<'ctx>>, Vec<bool>)>,
     pub call_id: usize,
     pub is_rough: bool,
     pub rng: StdRng,
 }
 
 impl<'ctx> Environment<'ctx> {
-    pub fn new(slice: &Slice<'ctx>, is_rough_mode: bool) -> Self {
+    pub fn new(slice: &Slice<'ctx>, is_rough_mode: bool, seed: u64) -> Self {
         Self {
             slice: slice.clone(),
             work_list: vec![],
             block_traces: vec![],
             call_id: 0,
             is_rough: is_rough_mode,
-            rng: match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
-                Ok(n) => StdRng::seed_from_u64(n.as_secs()),
-                Err(_) => StdRng::seed_from_u64(996996),
-            },
+            rng: StdRng::seed_from_u64(seed),
         }
     }
 
@@ -84,13 +102,14 @@ impl<'ctx> Environment<'ctx> {
         result
     }
 
-    pub fn add_block_trace(&mut self, block_trace: Vec<Block<'ctx>>) {
-        self.block_traces.push(block_trace)
+    pub fn add_block_trace(&mut self, block_trace: Vec<Block<'ctx>>, select_arms: Vec<bool>) {
+        self.block_traces.push((block_trace, select_arms))
     }
 
-    pub fn has_duplicate(&self, block_trace: &Vec<Block<'ctx>>) -> bool {
-        for other_block_trace in self.block_traces.iter() {
-            if block_trace.equals(other_block_trace) {
+    // The paths forked on a select go through the same blocks, so they are told apart by the arms taken
+    pub fn has_duplicate(&self, block_trace: &Vec<Block<'ctx>>, select_arms: &Vec<bool>) -> bool {
+        for (other_block_trace, other_select_arms) in self.block_traces.iter() {
+            if block_trace.equals(other_block_trace) && select_arms == other_select_arms {
                 return true;
             }
         }
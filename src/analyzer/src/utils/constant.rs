@@ -0,0 +1,50 @@
+use llir::values::*;
+
+// The constant stored at a location inside a constant global, e.g., `ops.open` or `table[2]` of a
+// `static const` table. Locations inside a `zeroinitializer` give the zero aggregate itself
+pub fn constant_at_location<'ctx>(location: Operand<'ctx>) -> Option<Constant<'ctx>> {
+    let (glob, indices) = match location {
+        Operand::Constant(Constant::Global(Global::Variable(glob))) => (glob, vec![]),
+        Operand::Constant(Constant::ConstExpr(ConstExpr::GetElementPtr(gep))) => match gep.location() {
+            Constant::Global(Global::Variable(glob)) => (glob, gep.indices()),
+            _ => return None,
+        },
+        Operand::Instruction(Instruction::GetElementPtr(gep)) => match gep.location() {
+            Operand::Constant(Constant::Global(Global::Variable(glob))) => {
+                let indices = gep
+                    .indices()
+                    .into_iter()
+                    .map(|index| match index {
+                        Operand::Constant(c) => Some(c),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                (glob, indices)
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let mut constant = glob.constant_initializer()?;
+
+    // The first index steps over the global itself
+    let mut indices = indices.into_iter();
+    if let Some(first) = indices.next() {
+        match first {
+            Constant::Int(i) if i.zext_value() == 0 => {}
+            _ => return None,
+        }
+    }
+    for index in indices {
+        let index = match index {
+            Constant::Int(i) => i.zext_value() as usize,
+            _ => return None,
+        };
+        constant = match constant {
+            Constant::Struct(_) | Constant::Array(_) => constant.element(index)?,
+            _ if constant.is_null_value() => return Some(constant),
+            _ => return None,
+        };
+    }
+    Some(constant)
+}
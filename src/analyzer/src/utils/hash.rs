@@ -0,0 +1,48 @@
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::path::PathBuf;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+const FNV_PRIME: u64 = 0x100000001b3;
+
+// The size of the chunks the files are read in
+const CHUNK_SIZE: usize = 1 << 16;
+
+fn fnv1a<'a, I>(mut hash: u64, bytes: I) -> u64
+where
+    I: IntoIterator<Item = &'a u8>,
+{
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+// FNV-1a hash. Unlike the std hasher, the result is stable across builds and runs
+pub fn stable_hash<'a, I>(bytes: I) -> u64
+where
+    I: IntoIterator<Item = &'a u8>,
+{
+    fnv1a(FNV_OFFSET_BASIS, bytes)
+}
+
+// Hash the contents of the files together with a string, e.g., the options. The files are streamed
+// through the hash in chunks, as the bytecode of a whole project may not fit in memory
+pub fn stable_hash_files(paths: &[PathBuf], s: &str) -> Result<u64, String> {
+    let mut hash = FNV_OFFSET_BASIS;
+    let mut buffer = vec![0; CHUNK_SIZE];
+    for path in paths {
+        let mut file = File::open(path).map_err(|_| format!("Cannot read {:?}", path))?;
+        loop {
+            match file.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(len) => hash = fnv1a(hash, &buffer[..len]),
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(_) => return Err(format!("Cannot read {:?}", path)),
+            }
+        }
+    }
+    Ok(fnv1a(hash, s.as_bytes()))
+}
@@ -0,0 +1,33 @@
+// The name keywords of functions preparing and finishing with an object, e.g., `kmalloc` and `kfree`
+pub const PRE_KEYWORDS: [&str; 8] = ["alloc", "new", "clone", "create", "dup", "init", "open", "_lock"];
+pub const POST_KEYWORDS: [&str; 7] = ["free", "release", "clear", "destroy", "clean", "close", "_unlock"];
+// The keywords of functions used both before and after others, e.g., `push` and `pop`
+pub const OTHER_KEYWORDS: [&str; 8] = ["fetch", "insert", "push", "pop", "read", "write", "encode", "decode"];
+// The prefixes of functions with variable arguments
+pub const VAR_ARG_KEYWORDS: [&str; 2] = ["print", "execl"];
+
+pub fn has_keyword(name: &str, keywords: &[&str]) -> bool {
+    let name = name.to_lowercase();
+    keywords.iter().any(|keyword| name.contains(keyword))
+}
+
+pub fn is_pre(name: &str) -> bool {
+    has_keyword(name, &PRE_KEYWORDS)
+}
+
+pub fn is_post(name: &str) -> bool {
+    has_keyword(name, &POST_KEYWORDS)
+}
+
+pub fn is_pre_seq(name: &str) -> bool {
+    is_pre(name) || has_keyword(name, &OTHER_KEYWORDS)
+}
+
+pub fn is_subsequent(name: &str) -> bool {
+    is_post(name) || has_keyword(name, &OTHER_KEYWORDS)
+}
+
+pub fn is_variable_argument(name: &str) -> bool {
+    let name = name.to_lowercase();
+    VAR_ARG_KEYWORDS.iter().any(|keyword| name.starts_with(keyword))
+}
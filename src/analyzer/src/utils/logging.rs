@@ -65,6 +65,16 @@ impl LoggingContext {
         self.log(format!("{} slices have been generated, dumping slices to json...", num_slices).as_str())
     }
 
+    pub fn log_skipped_slices(&mut self, num_skipped: usize, num_trimmed: usize) -> Result<(), String> {
+        if num_skipped > 0 {
+            self.log(format!("{} slices skipped for exceeding max number of blocks", num_skipped).as_str())?;
+        }
+        if num_trimmed > 0 {
+            self.log(format!("{} slices trimmed for exceeding max number of blocks", num_trimmed).as_str())?;
+        }
+        Ok(())
+    }
+
     pub fn log_dividing_batches(&mut self, use_batch: bool, num_batches: usize) -> Result<(), String> {
         if use_batch {
             self.log(format!("Slices dumped, dividing slices into {} batches...", num_batches).as_str())
@@ -11,7 +11,12 @@ pub struct MetaData {
     pub exceeding_length_trace_count: usize,
     pub timeout_trace_count: usize,
     pub unreachable_trace_count: usize,
+    pub unwound_trace_count: usize,
     pub explored_trace_count: usize,
+    pub skipped_slice_count: usize,
+    pub skipped_slices: Vec<serde_json::Value>,
+    pub trimmed_slice_count: usize,
+    pub trimmed_slices: Vec<serde_json::Value>,
 }
 
 impl MetaData {
@@ -25,7 +30,12 @@ impl MetaData {
             exceeding_length_trace_count: 0,
             timeout_trace_count: 0,
             unreachable_trace_count: 0,
+            unwound_trace_count: 0,
             explored_trace_count: 0,
+            skipped_slice_count: 0,
+            skipped_slices: vec![],
+            trimmed_slice_count: 0,
+            trimmed_slices: vec![],
         }
     }
 
@@ -39,10 +49,25 @@ impl MetaData {
             exceeding_length_trace_count: self.exceeding_length_trace_count + other.exceeding_length_trace_count,
             timeout_trace_count: self.timeout_trace_count + other.timeout_trace_count,
             unreachable_trace_count: self.unreachable_trace_count + other.unreachable_trace_count,
+            unwound_trace_count: self.unwound_trace_count + other.unwound_trace_count,
             explored_trace_count: self.explored_trace_count + other.explored_trace_count,
+            skipped_slice_count: self.skipped_slice_count + other.skipped_slice_count,
+            skipped_slices: self.skipped_slices.into_iter().chain(other.skipped_slices).collect(),
+            trimmed_slice_count: self.trimmed_slice_count + other.trimmed_slice_count,
+            trimmed_slices: self.trimmed_slices.into_iter().chain(other.trimmed_slices).collect(),
         }
     }
 
+    pub fn add_skipped_slice(&mut self, slice: serde_json::Value) {
+        self.skipped_slice_count += 1;
+        self.skipped_slices.push(slice);
+    }
+
+    pub fn add_trimmed_slice(&mut self, slice: serde_json::Value) {
+        self.trimmed_slice_count += 1;
+        self.trimmed_slices.push(slice);
+    }
+
     pub fn incr_proper(&mut self) {
         self.proper_trace_count += 1;
         self.explored_trace_count += 1;
@@ -83,20 +108,53 @@ impl MetaData {
         self.explored_trace_count += 1;
     }
 
+    pub fn incr_unwound(&mut self) {
+        self.unwound_trace_count += 1;
+        self.explored_trace_count += 1;
+    }
+
     pub fn dump(&self, filename: PathBuf) -> Result<(), String> {
-        crate::utils::dump_json(
-            &json!({
-                "proper_trace_count": self.proper_trace_count,
-                "path_unsat_trace_count": self.path_unsat_trace_count,
-                "branch_explored_trace_count": self.branch_explored_trace_count,
-                "duplicate_trace_count": self.duplicate_trace_count,
-                "no_target_trace_count": self.no_target_trace_count,
-                "exceeding_length_trace_count": self.exceeding_length_trace_count,
-                "timeout_trace_count": self.timeout_trace_count,
-                "unreachable_trace_count": self.unreachable_trace_count,
-                "explored_trace_count": self.explored_trace_count,
-            }),
-            filename,
-        )
+        crate::utils::dump_json(&self.to_json(), filename)
+    }
+
+    // Load the trace counts, e.g., from a completion record
+    pub fn from_json(json: &serde_json::Value) -> Option<Self> {
+        let count = |key: &str| json[key].as_u64().map(|n| n as usize);
+        Some(MetaData {
+            proper_trace_count: count("proper_trace_count")?,
+            path_unsat_trace_count: count("path_unsat_trace_count")?,
+            branch_explored_trace_count: count("branch_explored_trace_count")?,
+            duplicate_trace_count: count("duplicate_trace_count")?,
+            no_target_trace_count: count("no_target_trace_count")?,
+            exceeding_length_trace_count: count("exceeding_length_trace_count")?,
+            timeout_trace_count: count("timeout_trace_count")?,
+            unreachable_trace_count: count("unreachable_trace_count")?,
+            // Not in the records written before the count was added
+            unwound_trace_count: count("unwound_trace_count").unwrap_or(0),
+            explored_trace_count: count("explored_trace_count")?,
+            skipped_slice_count: 0,
+            skipped_slices: vec![],
+            trimmed_slice_count: 0,
+            trimmed_slices: vec![],
+        })
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "proper_trace_count": self.proper_trace_count,
+            "path_unsat_trace_count": self.path_unsat_trace_count,
+            "branch_explored_trace_count": self.branch_explored_trace_count,
+            "duplicate_trace_count": self.duplicate_trace_count,
+            "no_target_trace_count": self.no_target_trace_count,
+            "exceeding_length_trace_count": self.exceeding_length_trace_count,
+            "timeout_trace_count": self.timeout_trace_count,
+            "unreachable_trace_count": self.unreachable_trace_count,
+            "unwound_trace_count": self.unwound_trace_count,
+            "explored_trace_count": self.explored_trace_count,
+            "skipped_slice_count": self.skipped_slice_count,
+            "skipped_slices": self.skipped_slices,
+            "trimmed_slice_count": self.trimmed_slice_count,
+            "trimmed_slices": self.trimmed_slices,
+        })
     }
 }
@@ -1,10 +1,20 @@
 mod batching;
 mod cartesian;
+mod constant;
+mod hash;
 mod json;
+mod keyword;
 mod logging;
 mod metadata;
+mod module;
+mod random;
 pub use batching::*;
 pub use cartesian::*;
+pub use constant::*;
+pub use hash::*;
 pub use json::*;
+pub use keyword::*;
 pub use logging::*;
 pub use metadata::*;
+pub use module::*;
+pub use random::*;
@@ -0,0 +1,47 @@
+use llir::{Context, Module};
+use std::path::PathBuf;
+
+// Collect the bitcode or textual IR files of the inputs; a directory stands for all the `.bc` and `.ll` files in it
+pub fn bitcode_files(inputs: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    let mut files = vec![];
+    for input in inputs {
+        if input.is_dir() {
+            let mut bc_files = std::fs::read_dir(input)
+                .map_err(|_| format!("Cannot read input directory {:?}", input))?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.is_file() && path.extension().map_or(false, |ext| ext == "bc" || ext == "ll"))
+                .collect::<Vec<_>>();
+            bc_files.sort();
+            files.extend(bc_files);
+        } else {
+            files.push(input.clone());
+        }
+    }
+    if files.is_empty() {
+        Err(String::from("No bitcode or IR file in the inputs"))
+    } else {
+        Ok(files)
+    }
+}
+
+// Load the files and link them into one module for the whole-program analysis. A definition clashing
+// with one of the modules linked before, e.g., the same `main` in two programs, is made internal to its
+// module so that the linking does not fail, and is reported
+pub fn load_linked_module<'ctx>(llctx: &'ctx Context, files: &[PathBuf]) -> Result<Module<'ctx>, String> {
+    let llmod = llctx
+        .load_module(&files[0])
+        .map_err(|err| format!("{:?}: {}", files[0], err))?;
+    for file in &files[1..] {
+        let other = llctx.load_module(file).map_err(|err| format!("{:?}: {}", file, err))?;
+        let clashing = llmod.internalize_clashing_definitions(&other);
+        if !clashing.is_empty() {
+            eprintln!(
+                "{:?} defines {} already defined by the other inputs, analysed as internal to the module",
+                file,
+                clashing.join(", ")
+            );
+        }
+        llmod.link(other).map_err(|err| format!("{:?}: {}", file, err))?;
+    }
+    Ok(llmod)
+}
@@ -0,0 +1,28 @@
+//! Seeding of the random number generators used in the exploration
+//!
+//! Every RNG is derived from a global seed plus stable identifiers of the slice,
+//! so that the generated traces do not depend on the scheduling of threads.
+
+use std::time::SystemTime;
+
+use super::stable_hash;
+
+/// The seed from the current UNIX second, used when no seed is specified
+pub fn time_seed() -> u64 {
+    match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(n) => n.as_secs(),
+        Err(_) => 996996,
+    }
+}
+
+/// Derive a seed from the global seed, a name and an id, e.g., target name and slice id.
+///
+/// Uses a stable hash rather than the std hasher so that the result is the same across builds.
+pub fn derive_seed(seed: u64, name: &str, id: usize) -> u64 {
+    stable_hash(
+        seed.to_le_bytes()
+            .iter()
+            .chain(name.as_bytes().iter())
+            .chain((id as u64).to_le_bytes().iter()),
+    )
+}
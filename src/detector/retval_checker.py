@@ -7,7 +7,7 @@
 }
 e.g.
     "check": {
-        "check_cond": eq|ne|gt|ge|lt|le,
+        "check_cond": eq|ne|gt|ge|lt|le|in|not_in,
         "checked": true,
         "indir_checked": bool, 
         "compared_with_const": 0,